@@ -60,7 +60,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     // Notification payload
     let builder = DefaultNotificationBuilder::new()
-        .set_body(message.as_ref())
+        .set_body(message.as_str())
         .set_sound("default")
         .set_badge(1u32);
 
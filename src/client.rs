@@ -5,7 +5,8 @@ use crate::error::Error::ResponseError;
 use crate::signer::Signer;
 use hyper_alpn::AlpnConnector;
 
-use crate::request::payload::Payload;
+use crate::request::notification::PushType;
+use crate::request::payload::{Payload, PayloadError};
 use crate::response::Response;
 use http::header::{AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE};
 use hyper::{self, Body, Client as HttpClient, StatusCode};
@@ -108,10 +109,14 @@ impl Client {
 
     /// Send a notification payload.
     ///
+    /// Payloads larger than APNs accepts for their push type fail with a
+    /// `PayloadTooLarge` error, and payloads with an empty device token with
+    /// an `InvalidOptions` error, before anything is sent.
+    ///
     /// See [ErrorReason](enum.ErrorReason.html) for possible errors.
     #[cfg_attr(feature = "tracing", ::tracing::instrument)]
     pub async fn send(&self, payload: Payload<'_>) -> Result<Response, Error> {
-        let request = self.build_request(payload);
+        let request = self.build_request(payload)?;
         let requesting = self.http_client.request(request);
 
         let response = requesting.await?;
@@ -140,7 +145,11 @@ impl Client {
         }
     }
 
-    fn build_request(&self, payload: Payload<'_>) -> hyper::Request<Body> {
+    fn build_request(&self, payload: Payload<'_>) -> Result<hyper::Request<Body>, Error> {
+        if payload.device_token.is_empty() {
+            return Err(Error::InvalidOptions(String::from("The device token is empty")));
+        }
+
         let path = format!("https://{}/3/device/{}", self.endpoint, payload.device_token);
 
         let mut builder = hyper::Request::builder()
@@ -163,6 +172,9 @@ impl Client {
         if let Some(apns_topic) = payload.options.apns_topic {
             builder = builder.header("apns-topic", apns_topic.as_bytes());
         }
+        if let Some(apns_push_type) = payload.options.apns_push_type {
+            builder = builder.header("apns-push-type", apns_push_type.header_value());
+        }
         if let Some(ref signer) = self.signer {
             let auth = signer
                 .with_signature(|signature| format!("Bearer {}", signature))
@@ -171,11 +183,25 @@ impl Client {
             builder = builder.header(AUTHORIZATION, auth.as_bytes());
         }
 
-        let payload_json = payload.to_json_string().unwrap();
+        let limit = payload
+            .options
+            .apns_push_type
+            .unwrap_or(PushType::Alert)
+            .max_payload_size();
+        let payload_json = payload.to_vec()?;
+
+        if payload_json.len() > limit {
+            return Err(PayloadError::PayloadTooLarge {
+                size: payload_json.len(),
+                limit,
+            }
+            .into());
+        }
+
         builder = builder.header(CONTENT_LENGTH, format!("{}", payload_json.len()).as_bytes());
 
         let request_body = Body::from(payload_json);
-        builder.body(request_body).unwrap()
+        Ok(builder.body(request_body).unwrap())
     }
 }
 
@@ -185,6 +211,7 @@ mod tests {
     use crate::request::notification::DefaultNotificationBuilder;
     use crate::request::notification::NotificationBuilder;
     use crate::request::notification::{CollapseId, NotificationOptions, Priority};
+    use crate::request::payload::MAX_PAYLOAD_SIZE;
     use crate::signer::Signer;
     use http::header::{AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE};
     use hyper::Method;
@@ -201,7 +228,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         let builder = DefaultNotificationBuilder::new();
         let payload = builder.build("a_test_id", Default::default());
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
         let uri = format!("{}", request.uri());
 
         assert_eq!("https://api.push.apple.com/3/device/a_test_id", &uri);
@@ -212,7 +239,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         let builder = DefaultNotificationBuilder::new();
         let payload = builder.build("a_test_id", Default::default());
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Sandbox);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
         let uri = format!("{}", request.uri());
 
         assert_eq!("https://api.development.push.apple.com/3/device/a_test_id", &uri);
@@ -223,7 +250,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         let builder = DefaultNotificationBuilder::new();
         let payload = builder.build("a_test_id", Default::default());
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
 
         assert_eq!(&Method::POST, request.method());
     }
@@ -233,7 +260,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         let builder = DefaultNotificationBuilder::new();
         let payload = builder.build("a_test_id", Default::default());
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
 
         assert_eq!("application/json", request.headers().get(CONTENT_TYPE).unwrap());
     }
@@ -243,7 +270,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         let builder = DefaultNotificationBuilder::new();
         let payload = builder.build("a_test_id", Default::default());
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload.clone());
+        let request = client.build_request(payload.clone()).unwrap();
         let payload_json = payload.to_json_string().unwrap();
         let content_length = request.headers().get(CONTENT_LENGTH).unwrap().to_str().unwrap();
 
@@ -255,7 +282,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         let builder = DefaultNotificationBuilder::new();
         let payload = builder.build("a_test_id", Default::default());
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
 
         assert_eq!(None, request.headers().get(AUTHORIZATION));
     }
@@ -273,7 +300,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         let builder = DefaultNotificationBuilder::new();
         let payload = builder.build("a_test_id", Default::default());
         let client = Client::new(AlpnConnector::new(), Some(signer), Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
 
         assert_ne!(None, request.headers().get(AUTHORIZATION));
     }
@@ -283,7 +310,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         let builder = DefaultNotificationBuilder::new();
         let payload = builder.build("a_test_id", Default::default());
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
         let apns_priority = request.headers().get("apns-priority");
 
         assert_eq!(None, apns_priority);
@@ -302,12 +329,31 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         );
 
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
         let apns_priority = request.headers().get("apns-priority").unwrap();
 
         assert_eq!("5", apns_priority);
     }
 
+    #[test]
+    fn test_request_with_low_priority() {
+        let builder = DefaultNotificationBuilder::new();
+
+        let payload = builder.build(
+            "a_test_id",
+            NotificationOptions {
+                apns_priority: Some(Priority::Low),
+                ..Default::default()
+            },
+        );
+
+        let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
+        let request = client.build_request(payload).unwrap();
+        let apns_priority = request.headers().get("apns-priority").unwrap();
+
+        assert_eq!("1", apns_priority);
+    }
+
     #[test]
     fn test_request_with_high_priority() {
         let builder = DefaultNotificationBuilder::new();
@@ -321,7 +367,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         );
 
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
         let apns_priority = request.headers().get("apns-priority").unwrap();
 
         assert_eq!("10", apns_priority);
@@ -334,7 +380,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         let payload = builder.build("a_test_id", Default::default());
 
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
         let apns_id = request.headers().get("apns-id");
 
         assert_eq!(None, apns_id);
@@ -353,7 +399,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         );
 
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
         let apns_id = request.headers().get("apns-id").unwrap();
 
         assert_eq!("a-test-apns-id", apns_id);
@@ -366,7 +412,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         let payload = builder.build("a_test_id", Default::default());
 
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
         let apns_expiration = request.headers().get("apns-expiration");
 
         assert_eq!(None, apns_expiration);
@@ -385,7 +431,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         );
 
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
         let apns_expiration = request.headers().get("apns-expiration").unwrap();
 
         assert_eq!("420", apns_expiration);
@@ -398,7 +444,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         let payload = builder.build("a_test_id", Default::default());
 
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
         let apns_collapse_id = request.headers().get("apns-collapse-id");
 
         assert_eq!(None, apns_collapse_id);
@@ -417,7 +463,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         );
 
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
         let apns_collapse_id = request.headers().get("apns-collapse-id").unwrap();
 
         assert_eq!("a_collapse_id", apns_collapse_id);
@@ -430,7 +476,7 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         let payload = builder.build("a_test_id", Default::default());
 
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
         let apns_topic = request.headers().get("apns-topic");
 
         assert_eq!(None, apns_topic);
@@ -449,18 +495,81 @@ jDwmlD1Gg0yJt1e38djFwsxsfr5q2hv0Rj9fTEqAPr8H7mGm0wKxZ7iQ
         );
 
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload);
+        let request = client.build_request(payload).unwrap();
         let apns_topic = request.headers().get("apns-topic").unwrap();
 
         assert_eq!("a_topic", apns_topic);
     }
 
+    #[test]
+    fn test_request_with_default_apns_push_type() {
+        let builder = DefaultNotificationBuilder::new();
+        let payload = builder.build("a_test_id", Default::default());
+        let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
+        let request = client.build_request(payload).unwrap();
+        let apns_push_type = request.headers().get("apns-push-type");
+
+        assert_eq!(None, apns_push_type);
+    }
+
+    #[test]
+    fn test_request_with_an_apns_push_type() {
+        let builder = DefaultNotificationBuilder::new();
+
+        let payload = builder.build(
+            "a_test_id",
+            NotificationOptions {
+                apns_push_type: Some(PushType::Background),
+                ..Default::default()
+            },
+        );
+
+        let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
+        let request = client.build_request(payload).unwrap();
+        let apns_push_type = request.headers().get("apns-push-type").unwrap();
+
+        assert_eq!("background", apns_push_type);
+    }
+
+    #[test]
+    fn test_request_with_a_payload_too_large() {
+        let body = "a".repeat(MAX_PAYLOAD_SIZE);
+        let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
+
+        let payload = DefaultNotificationBuilder::new()
+            .set_body(&body)
+            .build("a_test_id", Default::default());
+
+        assert!(matches!(
+            client.build_request(payload),
+            Err(Error::PayloadError(PayloadError::PayloadTooLarge { .. }))
+        ));
+
+        let payload = DefaultNotificationBuilder::new().set_body(&body).build(
+            "a_test_id",
+            NotificationOptions {
+                apns_push_type: Some(PushType::Voip),
+                ..Default::default()
+            },
+        );
+
+        assert!(client.build_request(payload).is_ok());
+    }
+
+    #[test]
+    fn test_request_with_an_empty_device_token() {
+        let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
+        let payload = Payload::try_from(r#"{"aps":{"badge":1}}"#).unwrap();
+
+        assert!(matches!(client.build_request(payload), Err(Error::InvalidOptions(_))));
+    }
+
     #[tokio::test]
     async fn test_request_body() {
         let builder = DefaultNotificationBuilder::new();
         let payload = builder.build("a_test_id", Default::default());
         let client = Client::new(AlpnConnector::new(), None, Endpoint::Production);
-        let request = client.build_request(payload.clone());
+        let request = client.build_request(payload.clone()).unwrap();
 
         let body = hyper::body::to_bytes(request).await.unwrap();
         let body_str = String::from_utf8(body.to_vec()).unwrap();
@@ -1,5 +1,5 @@
 ///! Error and result module
-use crate::{response::Response, signer::SignerError};
+use crate::{request::payload::PayloadError, response::Response, signer::SignerError};
 use std::io;
 use thiserror::Error;
 
@@ -34,6 +34,10 @@ pub enum Error {
     #[error("Invalid options for APNs payload: {0}")]
     InvalidOptions(String),
 
+    /// The notification payload would not be accepted by APNs.
+    #[error("Invalid APNs payload: {0}")]
+    PayloadError(#[from] PayloadError),
+
     /// Error reading the certificate or private key.
     #[error("Error in reading a certificate file: {0}")]
     ReadError(#[from] io::Error),
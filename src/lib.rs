@@ -116,17 +116,25 @@ extern crate serde;
 #[macro_use]
 extern crate serde_json;
 
+#[doc(hidden)]
+pub use serde_json::json as __json;
+
 pub mod client;
 pub mod error;
+mod macros;
 pub mod request;
 pub mod response;
 mod signer;
 
 pub use crate::request::notification::{
-    CollapseId, DefaultNotificationBuilder, NotificationBuilder, NotificationOptions, Priority, WebNotificationBuilder,
-    WebPushAlert,
+    BoxedNotificationBuilder, CollapseId, DefaultNotificationBuilder, LiveActivityEvent,
+    LiveActivityNotificationBuilder, LiveActivityTimestamp, LocArg, LocalizedNotificationBuilder,
+    MdmNotificationBuilder, NotificationBuilder, NotificationOptions, NotificationOptionsBuilder, Priority, PushType,
+    SilentNotificationBuilder, WebNotificationBuilder, WebPushAlert,
 };
 
+pub use crate::request::payload::{APSSound, InterruptionLevel, PayloadError};
+
 pub use crate::response::{ErrorBody, ErrorReason, Response};
 
 pub use crate::client::{Client, Endpoint};
@@ -0,0 +1,260 @@
+/// Creates a [Payload](request/payload/struct.Payload.html) from a literal
+/// description, expanding to
+/// [DefaultNotificationBuilder](request/notification/struct.DefaultNotificationBuilder.html)
+/// calls.
+///
+/// The keys are `alert` with `title`, `subtitle` and `body`, `badge`,
+/// `sound`, `category`, `thread_id`, `mutable_content`, `content_available`,
+/// `custom`, `device_token` and `options`. The `custom` values use the
+/// syntax of `serde_json::json!`, with negative numbers in parentheses. The
+/// device token defaults to an empty string and the options to their
+/// defaults.
+///
+/// An unknown key is a compile error, as is `content_available` together
+/// with an `alert` or a `sound`, `mutable_content` without an `alert`, and a
+/// `custom` key that is empty, `aps` or not a string literal. The messages
+/// are pinned by the UI tests in `tests/ui`.
+///
+/// # Panics
+///
+/// If a `custom` key spells `aps` with escapes, such as `"\x61ps"`, which the
+/// compile time check cannot see, or if the `custom` data makes the payload
+/// larger than the size limit of its push type.
+///
+/// # Example
+///
+/// ```rust
+/// # use a2::payload;
+/// # fn main() {
+/// let payload = payload! {
+///     alert: { title: "Hi", body: "There" },
+///     badge: 3,
+///     sound: "default",
+///     custom: { "msg_id": 42, "tags": ["a", "b"] },
+/// };
+///
+/// assert_eq!(
+///     "{\"aps\":{\"alert\":{\"body\":\"There\",\"title\":\"Hi\"},\"badge\":3,\"sound\":\"default\"},\"msg_id\":42,\"tags\":[\"a\",\"b\"]}",
+///     &payload.to_json_string().unwrap()
+/// );
+/// # }
+/// ```
+#[macro_export]
+macro_rules! payload {
+    ($($fields:tt)*) => {
+        $crate::__payload!(
+            ($crate::request::notification::DefaultNotificationBuilder::new())
+            ("")
+            (::core::default::Default::default())
+            []
+            [false false false false]
+            $($fields)*
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __payload {
+    (($builder:expr) ($token:expr) ($options:expr) [$(($key:tt, $value:tt))*] [$($flags:tt)*] $(,)?) => {{
+        $crate::__payload_check!($($flags)*);
+        #[allow(unused_mut)]
+        let mut payload = $crate::request::notification::NotificationBuilder::build($builder, $token, $options);
+        $(
+            payload
+                .add_custom_data($crate::__payload_key!($key), &$crate::__json!($value))
+                .expect("invalid custom data in payload!");
+        )*
+        payload
+    }};
+    ($builder:tt $token:tt $options:tt $custom:tt $flags:tt alert: {} $(, $($rest:tt)*)?) => {
+        $crate::__payload!($builder $token $options $custom $flags $($($rest)*)?)
+    };
+    (($builder:expr) $token:tt $options:tt $custom:tt [$_alert:tt $sound:tt $available:tt $mutable:tt] alert: { $($alert:tt)+ } $(, $($rest:tt)*)?) => {
+        $crate::__payload!(($crate::__payload_alert!($builder, $($alert)+)) $token $options $custom [true $sound $available $mutable] $($($rest)*)?)
+    };
+    (($builder:expr) $token:tt $options:tt $custom:tt $flags:tt badge: $badge:expr $(, $($rest:tt)*)?) => {
+        $crate::__payload!(($builder.set_badge($badge)) $token $options $custom $flags $($($rest)*)?)
+    };
+    (($builder:expr) $token:tt $options:tt $custom:tt [$alert:tt $_sound:tt $available:tt $mutable:tt] sound: $sound:expr $(, $($rest:tt)*)?) => {
+        $crate::__payload!(($builder.set_sound($sound)) $token $options $custom [$alert true $available $mutable] $($($rest)*)?)
+    };
+    (($builder:expr) $token:tt $options:tt $custom:tt $flags:tt category: $category:expr $(, $($rest:tt)*)?) => {
+        $crate::__payload!(($builder.set_category($category)) $token $options $custom $flags $($($rest)*)?)
+    };
+    (($builder:expr) $token:tt $options:tt $custom:tt $flags:tt thread_id: $thread_id:expr $(, $($rest:tt)*)?) => {
+        $crate::__payload!(($builder.set_thread_id($thread_id)) $token $options $custom $flags $($($rest)*)?)
+    };
+    (($builder:expr) $token:tt $options:tt $custom:tt [$alert:tt $sound:tt $available:tt $_mutable:tt] mutable_content: true $(, $($rest:tt)*)?) => {
+        $crate::__payload!(($builder.set_mutable_content()) $token $options $custom [$alert $sound $available true] $($($rest)*)?)
+    };
+    ($builder:tt $token:tt $options:tt $custom:tt $flags:tt mutable_content: false $(, $($rest:tt)*)?) => {
+        $crate::__payload!($builder $token $options $custom $flags $($($rest)*)?)
+    };
+    (($builder:expr) $token:tt $options:tt $custom:tt [$alert:tt $sound:tt $_available:tt $mutable:tt] content_available: true $(, $($rest:tt)*)?) => {
+        $crate::__payload!(($builder.set_content_available()) $token $options $custom [$alert $sound true $mutable] $($($rest)*)?)
+    };
+    ($builder:tt $token:tt $options:tt $custom:tt $flags:tt content_available: false $(, $($rest:tt)*)?) => {
+        $crate::__payload!($builder $token $options $custom $flags $($($rest)*)?)
+    };
+    ($builder:tt $token:tt $options:tt [$($custom:tt)*] $flags:tt custom: { $($key:tt: $value:tt),* $(,)? } $(, $($rest:tt)*)?) => {
+        $crate::__payload!($builder $token $options [$($custom)* $(($key, $value))*] $flags $($($rest)*)?)
+    };
+    ($builder:tt $token:tt $options:tt $custom:tt $flags:tt custom: $($rest:tt)*) => {
+        ::core::compile_error!("payload! expects `custom: { \"key\": value, ... }` with string literal keys")
+    };
+    ($builder:tt $_token:tt $options:tt $custom:tt $flags:tt device_token: $token:expr $(, $($rest:tt)*)?) => {
+        $crate::__payload!($builder ($token) $options $custom $flags $($($rest)*)?)
+    };
+    ($builder:tt $token:tt $_options:tt $custom:tt $flags:tt options: $options:expr $(, $($rest:tt)*)?) => {
+        $crate::__payload!($builder $token ($options) $custom $flags $($($rest)*)?)
+    };
+    ($builder:tt $token:tt $options:tt $custom:tt $flags:tt $key:ident $($rest:tt)*) => {
+        ::core::compile_error!(::core::concat!("unknown or invalid payload! key `", ::core::stringify!($key), "`"))
+    };
+}
+
+/// Checks the `[alert sound content_available mutable_content]` flags
+/// collected by `__payload!` for combinations APNs rejects.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __payload_check {
+    (true $sound:tt true $mutable:tt) => {
+        ::core::compile_error!("payload! cannot combine `content_available` with an `alert`")
+    };
+    ($alert:tt true true $mutable:tt) => {
+        ::core::compile_error!("payload! cannot combine `content_available` with a `sound`")
+    };
+    (false $sound:tt $available:tt true) => {
+        ::core::compile_error!("payload! needs an `alert` for `mutable_content`")
+    };
+    ($alert:tt $sound:tt $available:tt $mutable:tt) => {};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __payload_key {
+    ("aps") => {
+        ::core::compile_error!("the `aps` custom key is reserved in payload!")
+    };
+    ("") => {
+        ::core::compile_error!("empty custom key in payload!")
+    };
+    ($key:literal) => {
+        $key
+    };
+    ($key:tt) => {
+        ::core::compile_error!(::core::concat!(
+            "custom keys in payload! must be string literals, found `",
+            ::core::stringify!($key),
+            "`"
+        ))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __payload_alert {
+    ($builder:expr $(,)?) => {
+        $builder
+    };
+    ($builder:expr, title: $title:expr $(, $($rest:tt)*)?) => {
+        $crate::__payload_alert!($builder.set_title($title), $($($rest)*)?)
+    };
+    ($builder:expr, subtitle: $subtitle:expr $(, $($rest:tt)*)?) => {
+        $crate::__payload_alert!($builder.set_subtitle($subtitle), $($($rest)*)?)
+    };
+    ($builder:expr, body: $body:expr $(, $($rest:tt)*)?) => {
+        $crate::__payload_alert!($builder.set_body($body), $($($rest)*)?)
+    };
+    ($builder:expr, $key:ident $($rest:tt)*) => {
+        ::core::compile_error!(::core::concat!("unknown alert key `", ::core::stringify!($key), "` in payload!"))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::request::notification::{NotificationOptions, Priority};
+
+    #[test]
+    fn test_payload_macro_alert() {
+        let payload = payload! {
+            alert: {
+                title: "Hi",
+                subtitle: "From Anna",
+                body: String::from("There"),
+            },
+            badge: 1 + 2,
+            sound: "default",
+            category: "MESSAGE",
+            thread_id: "chat-anna",
+            mutable_content: true,
+        };
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "title": "Hi",
+                    "subtitle": "From Anna",
+                    "body": "There",
+                },
+                "badge": 3,
+                "sound": "default",
+                "category": "MESSAGE",
+                "thread-id": "chat-anna",
+                "mutable-content": 1,
+            }
+        })
+        .to_string();
+
+        assert_eq!("", payload.device_token);
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_payload_macro_silent() {
+        let payload = payload! {
+            content_available: true,
+            mutable_content: false,
+            custom: {
+                "sync": { "mailbox": "inbox", "since": null },
+                "offset": (-1),
+            },
+            device_token: "device-token",
+            options: NotificationOptions {
+                apns_priority: Some(Priority::Normal),
+                ..Default::default()
+            },
+        };
+
+        let expected_payload = json!({
+            "aps": {
+                "content-available": 1
+            },
+            "offset": -1,
+            "sync": {
+                "mailbox": "inbox",
+                "since": null
+            }
+        })
+        .to_string();
+
+        assert_eq!("device-token", payload.device_token);
+        assert_eq!(Some(Priority::Normal), payload.options.apns_priority);
+        assert!(payload.is_background_only());
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_payload_macro_without_keys() {
+        let payload = payload! {};
+
+        assert_eq!("{\"aps\":{}}", payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid custom data")]
+    fn test_payload_macro_with_escaped_reserved_custom_key() {
+        let _payload = payload! { custom: { "\x61ps": 1 } };
+    }
+}
@@ -1,15 +1,124 @@
 ///! The `aps` notification content builders
 mod default;
+mod live_activity;
+mod localized;
+mod mdm;
 mod options;
+mod silent;
 mod web;
 
-pub use self::default::{DefaultAlert, DefaultNotificationBuilder};
-pub use self::options::{CollapseId, NotificationOptions, Priority};
+pub use self::default::{DefaultAlert, DefaultNotificationBuilder, LocArg};
+pub use self::live_activity::{LiveActivityEvent, LiveActivityNotificationBuilder, LiveActivityTimestamp};
+pub use self::localized::LocalizedNotificationBuilder;
+pub use self::mdm::MdmNotificationBuilder;
+pub use self::options::{CollapseId, NotificationOptions, NotificationOptionsBuilder, Priority, PushType};
+pub use self::silent::SilentNotificationBuilder;
 pub use self::web::{WebNotificationBuilder, WebPushAlert};
 
+use crate::error::Error;
 use crate::request::payload::Payload;
 
 pub trait NotificationBuilder<'a> {
     /// Generates the request payload to be send with the `Client`.
     fn build(self, device_token: &'a str, options: NotificationOptions<'a>) -> Payload<'a>;
+
+    /// Generates the request payload, returning an error if it is larger than
+    /// APNs accepts or fails [validation](../payload/struct.Payload.html#method.validate).
+    fn try_build(self, device_token: &'a str, options: NotificationOptions<'a>) -> Result<Payload<'a>, Error>
+    where
+        Self: Sized,
+    {
+        let payload = self.build(device_token, options);
+        payload.validate()?;
+        payload.validate_size(payload.options.apns_push_type.unwrap_or(PushType::Alert))?;
+
+        Ok(payload)
+    }
+}
+
+/// A [NotificationBuilder](trait.NotificationBuilder.html) that can be built
+/// from behind a `Box`, for choosing the kind of notification at runtime. Every
+/// builder implements it, and a `Box<dyn BoxedNotificationBuilder>` is a
+/// `NotificationBuilder` itself.
+///
+/// ```rust
+/// # use a2::request::notification::{
+/// #     BoxedNotificationBuilder, DefaultNotificationBuilder, NotificationBuilder, SilentNotificationBuilder,
+/// # };
+/// # fn main() {
+/// let builder: Box<dyn BoxedNotificationBuilder> = match "sync" {
+///     "sync" => Box::new(SilentNotificationBuilder::new()),
+///     _ => Box::new(DefaultNotificationBuilder::new().set_body("a body")),
+/// };
+///
+/// let payload = builder.build("token", Default::default());
+///
+/// assert_eq!(
+///     "{\"aps\":{\"content-available\":1}}",
+///     &payload.to_json_string().unwrap()
+/// );
+/// # }
+/// ```
+pub trait BoxedNotificationBuilder<'a> {
+    /// Generates the request payload from a boxed builder.
+    fn build_boxed(self: Box<Self>, device_token: &'a str, options: NotificationOptions<'a>) -> Payload<'a>;
+}
+
+impl<'a, T> BoxedNotificationBuilder<'a> for T
+where
+    T: NotificationBuilder<'a>,
+{
+    fn build_boxed(self: Box<Self>, device_token: &'a str, options: NotificationOptions<'a>) -> Payload<'a> {
+        (*self).build(device_token, options)
+    }
+}
+
+impl<'a> NotificationBuilder<'a> for Box<dyn BoxedNotificationBuilder<'a> + 'a> {
+    fn build(self, device_token: &'a str, options: NotificationOptions<'a>) -> Payload<'a> {
+        self.build_boxed(device_token, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mixed_builders_behind_a_box() {
+        let alert = WebPushAlert::new("Hello", "World", "View");
+
+        let builders: Vec<Box<dyn BoxedNotificationBuilder>> = vec![
+            Box::new(DefaultNotificationBuilder::new().set_body("a body")),
+            Box::new(LocalizedNotificationBuilder::new("NEW_MESSAGE", ["Anna"])),
+            Box::new(SilentNotificationBuilder::new()),
+            Box::new(WebNotificationBuilder::new(alert, &["arg"])),
+            Box::new(MdmNotificationBuilder::new("push-magic")),
+            Box::new(LiveActivityNotificationBuilder::new(LiveActivityEvent::End, 1685952000)),
+        ];
+
+        let payloads: Vec<String> = builders
+            .into_iter()
+            .map(|builder| {
+                builder
+                    .build("device-token", Default::default())
+                    .to_json_string()
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                "{\"aps\":{\"alert\":{\"body\":\"a body\"}}}",
+                "{\"aps\":{\"alert\":{\"loc-args\":[\"Anna\"],\"loc-key\":\"NEW_MESSAGE\"}}}",
+                "{\"aps\":{\"content-available\":1}}",
+                "{\"aps\":{\"alert\":{\"action\":\"View\",\"body\":\"World\",\"title\":\"Hello\"},\"url-args\":[\"arg\"]}}",
+                "{\"mdm\":\"push-magic\"}",
+                "{\"aps\":{\"event\":\"end\",\"timestamp\":1685952000}}",
+            ],
+            payloads
+        );
+
+        let boxed: Box<dyn BoxedNotificationBuilder> = Box::new(SilentNotificationBuilder::new());
+        assert!(boxed.try_build("device-token", Default::default()).is_ok());
+    }
 }
@@ -1,37 +1,273 @@
+use crate::error::Error;
 use crate::request::notification::{NotificationBuilder, NotificationOptions};
-use crate::request::payload::{APSAlert, Payload, APS};
+use crate::request::payload::{
+    check_custom_data_key, APSAlert, APSSound, InterruptionLevel, Payload, PayloadError, APS,
+};
 
+use serde_json::Value;
 use std::{borrow::Cow, collections::BTreeMap};
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+/// An argument substituted into a localized string. Numbers are sent as JSON
+/// numbers, so the device can format them for the locale with `%d` and `%f`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum LocArg<'a> {
+    /// A string argument
+    Str(Cow<'a, str>),
+    /// An integer argument
+    Int(i64),
+    /// A floating point argument
+    Float(f64),
+}
+
+impl<'a> From<&'a str> for LocArg<'a> {
+    fn from(arg: &'a str) -> Self {
+        LocArg::Str(Cow::Borrowed(arg))
+    }
+}
+
+impl<'a> From<String> for LocArg<'a> {
+    fn from(arg: String) -> Self {
+        LocArg::Str(Cow::Owned(arg))
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for LocArg<'a> {
+    fn from(arg: Cow<'a, str>) -> Self {
+        LocArg::Str(arg)
+    }
+}
+
+impl<'a, 'b> From<&'a &'b str> for LocArg<'b> {
+    fn from(arg: &'a &'b str) -> Self {
+        LocArg::Str(Cow::Borrowed(*arg))
+    }
+}
+
+impl<'a> From<&'a String> for LocArg<'a> {
+    fn from(arg: &'a String) -> Self {
+        LocArg::Str(Cow::Borrowed(arg))
+    }
+}
+
+impl<'a, 'b> From<&'a &'b String> for LocArg<'b> {
+    fn from(arg: &'a &'b String) -> Self {
+        LocArg::Str(Cow::Borrowed(arg.as_str()))
+    }
+}
+
+impl<'a, 'b: 'a> From<&'a Cow<'b, str>> for LocArg<'a> {
+    fn from(arg: &'a Cow<'b, str>) -> Self {
+        LocArg::Str(Cow::Borrowed(arg))
+    }
+}
+
+impl<'a, 'b: 'a> From<&'a LocArg<'b>> for LocArg<'a> {
+    fn from(arg: &'a LocArg<'b>) -> Self {
+        match arg {
+            LocArg::Str(arg) => LocArg::Str(Cow::Borrowed(arg)),
+            LocArg::Int(arg) => LocArg::Int(*arg),
+            LocArg::Float(arg) => LocArg::Float(*arg),
+        }
+    }
+}
+
+impl<'a> From<i32> for LocArg<'a> {
+    fn from(arg: i32) -> Self {
+        LocArg::Int(arg.into())
+    }
+}
+
+impl<'a> From<&i32> for LocArg<'a> {
+    fn from(arg: &i32) -> Self {
+        LocArg::Int((*arg).into())
+    }
+}
+
+impl<'a> From<u32> for LocArg<'a> {
+    fn from(arg: u32) -> Self {
+        LocArg::Int(arg.into())
+    }
+}
+
+impl<'a> From<&u32> for LocArg<'a> {
+    fn from(arg: &u32) -> Self {
+        LocArg::Int((*arg).into())
+    }
+}
+
+impl<'a> From<i64> for LocArg<'a> {
+    fn from(arg: i64) -> Self {
+        LocArg::Int(arg)
+    }
+}
+
+impl<'a> From<&i64> for LocArg<'a> {
+    fn from(arg: &i64) -> Self {
+        LocArg::Int(*arg)
+    }
+}
+
+impl<'a> From<f64> for LocArg<'a> {
+    fn from(arg: f64) -> Self {
+        LocArg::Float(arg)
+    }
+}
+
+impl<'a> From<&f64> for LocArg<'a> {
+    fn from(arg: &f64) -> Self {
+        LocArg::Float(*arg)
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct DefaultAlert<'a> {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    title: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    title: Option<Cow<'a, str>>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
-    subtitle: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    subtitle: Option<Cow<'a, str>>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
-    body: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    body: Option<Cow<'a, str>>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
-    title_loc_key: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    title_loc_key: Option<Cow<'a, str>>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
-    title_loc_args: Option<Vec<Cow<'a, str>>>,
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    title_loc_args: Option<Vec<LocArg<'a>>>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
-    action_loc_key: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    subtitle_loc_key: Option<Cow<'a, str>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    loc_key: Option<&'a str>,
+    subtitle_loc_args: Option<Vec<LocArg<'a>>>,
+
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    action_loc_key: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    loc_key: Option<Cow<'a, str>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    loc_args: Option<Vec<Cow<'a, str>>>,
+    loc_args: Option<Vec<LocArg<'a>>>,
+
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    launch_image: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    summary_arg: Option<Cow<'a, str>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    launch_image: Option<&'a str>,
+    summary_arg_count: Option<u32>,
+}
+
+impl<'a> DefaultAlert<'a> {
+    pub(crate) fn with_title_and_body(title: Cow<'a, str>, body: Cow<'a, str>) -> Self {
+        DefaultAlert {
+            title: Some(title),
+            body: Some(body),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn body_mut(&mut self) -> Option<&mut Cow<'a, str>> {
+        self.body.as_mut()
+    }
+
+    /// True if the alert has no text, or key of a localized text, to show.
+    pub(crate) fn is_empty(&self) -> bool {
+        [
+            &self.title,
+            &self.subtitle,
+            &self.body,
+            &self.title_loc_key,
+            &self.subtitle_loc_key,
+            &self.loc_key,
+        ]
+        .iter()
+        .all(|text| text.as_deref().unwrap_or_default().is_empty())
+    }
+
+    /// Checks the `loc-args` match the number of format specifiers, such as
+    /// `%@` or `%1$@`, in the localized `template` of the `loc-key`. With
+    /// positional specifiers the highest position counts.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # use a2::request::payload::APSAlert;
+    /// # fn main() {
+    /// let payload = DefaultNotificationBuilder::new()
+    ///     .set_loc_key("GAME_PLAY_REQUEST_FORMAT")
+    ///     .set_loc_args(&["Jenna", "Frank"])
+    ///     .build("token", Default::default());
+    ///
+    /// if let Some(APSAlert::Default(alert)) = &payload.aps.alert {
+    ///     assert!(alert.validate_loc_args("%@ and %@ have invited you to play").is_ok());
+    ///     assert!(alert.validate_loc_args("%1$@ invited you to play").is_err());
+    /// }
+    /// # }
+    /// ```
+    pub fn validate_loc_args(&self, template: &str) -> Result<(), Error> {
+        validate_args("loc-args", self.loc_args.as_deref(), template)
+    }
+
+    /// Checks the `title-loc-args` match the number of format specifiers in
+    /// the localized `template` of the `title-loc-key`.
+    pub fn validate_title_loc_args(&self, template: &str) -> Result<(), Error> {
+        validate_args("title-loc-args", self.title_loc_args.as_deref(), template)
+    }
+
+    /// Checks the `subtitle-loc-args` match the number of format specifiers in
+    /// the localized `template` of the `subtitle-loc-key`.
+    pub fn validate_subtitle_loc_args(&self, template: &str) -> Result<(), Error> {
+        validate_args("subtitle-loc-args", self.subtitle_loc_args.as_deref(), template)
+    }
+}
+
+fn validate_args(key: &'static str, args: Option<&[LocArg]>, template: &str) -> Result<(), Error> {
+    let expected = format_argument_count(template);
+    let actual = args.map_or(0, |args| args.len());
+
+    if expected != actual {
+        return Err(PayloadError::LocArgsMismatch { key, expected, actual }.into());
+    }
+
+    Ok(())
+}
+
+/// The number of arguments a format string consumes: the count of
+/// sequential specifiers or the highest positional one, whichever is larger.
+fn format_argument_count(template: &str) -> usize {
+    let mut sequential = 0;
+    let mut max_position = 0;
+    let mut rest = template;
+
+    while let Some(start) = rest.find('%') {
+        rest = &rest[start + 1..];
+
+        if let Some(after) = rest.strip_prefix('%') {
+            rest = after;
+            continue;
+        }
+
+        if rest.is_empty() {
+            break;
+        }
+
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+        match rest[digits..].strip_prefix('$') {
+            Some(after) if digits > 0 => {
+                max_position = max_position.max(rest[..digits].parse().unwrap_or(0));
+                rest = after;
+            }
+            _ => sequential += 1,
+        }
+    }
+
+    sequential.max(max_position)
 }
 
 /// A builder to create an APNs payload.
@@ -64,10 +300,16 @@ pub struct DefaultAlert<'a> {
 pub struct DefaultNotificationBuilder<'a> {
     alert: DefaultAlert<'a>,
     badge: Option<u32>,
-    sound: Option<&'a str>,
-    category: Option<&'a str>,
-    mutable_content: u8,
+    sound: Option<APSSound<'a>>,
+    category: Option<Cow<'a, str>>,
+    mutable_content: Option<u8>,
     content_available: Option<u8>,
+    thread_id: Option<Cow<'a, str>>,
+    target_content_id: Option<Cow<'a, str>>,
+    interruption_level: Option<InterruptionLevel>,
+    relevance_score: Option<f64>,
+    filter_criteria: Option<Cow<'a, str>>,
+    media_attachment: Option<(Cow<'a, str>, Cow<'a, str>)>,
     has_edited_alert: bool,
 }
 
@@ -83,7 +325,7 @@ impl<'a> DefaultNotificationBuilder<'a> {
     ///     .build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"alert\":{\"body\":\"a body\",\"title\":\"a title\"},\"mutable-content\":0}}",
+    ///     "{\"aps\":{\"alert\":{\"body\":\"a body\",\"title\":\"a title\"}}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
@@ -96,20 +338,55 @@ impl<'a> DefaultNotificationBuilder<'a> {
                 body: None,
                 title_loc_key: None,
                 title_loc_args: None,
+                subtitle_loc_key: None,
+                subtitle_loc_args: None,
                 action_loc_key: None,
                 loc_key: None,
                 loc_args: None,
                 launch_image: None,
+                summary_arg: None,
+                summary_arg_count: None,
             },
             badge: None,
             sound: None,
             category: None,
-            mutable_content: 0,
+            mutable_content: None,
             content_available: None,
+            thread_id: None,
+            target_content_id: None,
+            interruption_level: None,
+            relevance_score: None,
+            filter_criteria: None,
+            media_attachment: None,
             has_edited_alert: false,
         }
     }
 
+    /// Creates a new builder for the common alert with a title and a body,
+    /// which cannot end up empty. Keys left unset are omitted.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let payload = DefaultNotificationBuilder::with_title_and_body("a title", "a body")
+    ///     .set_sound("ping")
+    ///     .set_badge(2)
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"body\":\"a body\",\"title\":\"a title\"},\"badge\":2,\"sound\":\"ping\"}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn with_title_and_body<T, B>(title: T, body: B) -> DefaultNotificationBuilder<'a>
+    where
+        T: Into<Cow<'a, str>>,
+        B: Into<Cow<'a, str>>,
+    {
+        Self::new().set_title(title).set_body(body)
+    }
+
     /// Set the title of the notification.
     /// Apple Watch displays this string in the short look notification interface.
     /// Specify a string that’s quickly understood by the user.
@@ -122,13 +399,16 @@ impl<'a> DefaultNotificationBuilder<'a> {
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"mutable-content\":0}}",
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"}}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
     /// ```
-    pub fn set_title(mut self, title: &'a str) -> Self {
-        self.alert.title = Some(title);
+    pub fn set_title<S>(mut self, title: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.alert.title = Some(title.into());
         self.has_edited_alert = true;
         self
     }
@@ -143,18 +423,22 @@ impl<'a> DefaultNotificationBuilder<'a> {
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"alert\":{\"subtitle\":\"a subtitle\"},\"mutable-content\":0}}",
+    ///     "{\"aps\":{\"alert\":{\"subtitle\":\"a subtitle\"}}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
     /// ```
-    pub fn set_subtitle(mut self, subtitle: &'a str) -> Self {
-        self.alert.subtitle = Some(subtitle);
+    pub fn set_subtitle<S>(mut self, subtitle: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.alert.subtitle = Some(subtitle.into());
         self.has_edited_alert = true;
         self
     }
 
-    /// Sets the content of the alert message.
+    /// Sets the content of the alert message. Like the title and subtitle, it
+    /// can be borrowed or an owned `String`.
     ///
     /// ```rust
     /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
@@ -164,18 +448,23 @@ impl<'a> DefaultNotificationBuilder<'a> {
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"alert\":{\"body\":\"a body\"},\"mutable-content\":0}}",
+    ///     "{\"aps\":{\"alert\":{\"body\":\"a body\"}}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
     /// ```
-    pub fn set_body(mut self, body: &'a str) -> Self {
-        self.alert.body = Some(body);
+    pub fn set_body<S>(mut self, body: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.alert.body = Some(body.into());
         self.has_edited_alert = true;
         self
     }
 
-    /// A number to show on a badge on top of the app icon.
+    /// A number to show on a badge on top of the app icon. Zero removes the
+    /// badge, as does [clear_badge](#method.clear_badge), and leaving the
+    /// value unset keeps the current badge as it is.
     ///
     /// ```rust
     /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
@@ -185,7 +474,7 @@ impl<'a> DefaultNotificationBuilder<'a> {
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"badge\":4,\"mutable-content\":0}}",
+    ///     "{\"aps\":{\"badge\":4}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
@@ -195,7 +484,29 @@ impl<'a> DefaultNotificationBuilder<'a> {
         self
     }
 
-    /// File name of the custom sound to play when receiving the notification.
+    /// Removes the badge from the app icon by sending `badge: 0`, unlike
+    /// leaving the badge unset, which keeps it as it is.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let builder = DefaultNotificationBuilder::new()
+    ///     .clear_badge();
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"badge\":0}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn clear_badge(self) -> Self {
+        self.set_badge(0)
+    }
+
+    /// File name of the custom sound to play when receiving the notification,
+    /// or a critical alert sound created with
+    /// [APSSound::critical](../payload/enum.APSSound.html#method.critical).
     ///
     /// ```rust
     /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
@@ -206,16 +517,65 @@ impl<'a> DefaultNotificationBuilder<'a> {
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"mutable-content\":0,\"sound\":\"ping\"}}",
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"sound\":\"ping\"}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
     /// ```
-    pub fn set_sound(mut self, sound: &'a str) -> Self {
-        self.sound = Some(sound);
+    pub fn set_sound<S>(mut self, sound: S) -> Self
+    where
+        S: Into<APSSound<'a>>,
+    {
+        self.sound = Some(sound.into());
         self
     }
 
+    /// Play the system sound for notifications.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut builder = DefaultNotificationBuilder::new()
+    ///     .set_title("a title")
+    ///     .set_default_sound();
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"sound\":\"default\"}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_default_sound(self) -> Self {
+        self.set_sound(APSSound::DEFAULT)
+    }
+
+    /// Play a critical alert sound with the given file name and a volume
+    /// between 0.0 and 1.0. Returns an error if the volume is out of range.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut builder = DefaultNotificationBuilder::new()
+    ///     .set_title("a title")
+    ///     .set_critical_sound("alarm.aiff", 0.8)
+    ///     .unwrap();
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"sound\":{\"critical\":1,\"name\":\"alarm.aiff\",\"volume\":0.8}}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_critical_sound<S>(mut self, name: S, volume: f64) -> Result<Self, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.sound = Some(APSSound::critical(name, volume)?);
+        Ok(self)
+    }
+
     /// When a notification includes the category key, the system displays the
     /// actions for that category as buttons in the banner or alert interface.
     ///
@@ -228,13 +588,16 @@ impl<'a> DefaultNotificationBuilder<'a> {
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"category\":\"cat1\",\"mutable-content\":0}}",
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"category\":\"cat1\"}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
     /// ```
-    pub fn set_category(mut self, category: &'a str) -> Self {
-        self.category = Some(category);
+    pub fn set_category<S>(mut self, category: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.category = Some(category.into());
         self
     }
 
@@ -249,13 +612,16 @@ impl<'a> DefaultNotificationBuilder<'a> {
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\",\"title-loc-key\":\"play\"},\"mutable-content\":0}}",
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\",\"title-loc-key\":\"play\"}}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
     /// ```
-    pub fn set_title_loc_key(mut self, key: &'a str) -> Self {
-        self.alert.title_loc_key = Some(key);
+    pub fn set_title_loc_key<S>(mut self, key: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.alert.title_loc_key = Some(key.into());
         self.has_edited_alert = true;
         self
     }
@@ -271,22 +637,76 @@ impl<'a> DefaultNotificationBuilder<'a> {
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\",\"title-loc-args\":[\"foo\",\"bar\"]},\"mutable-content\":0}}",
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\",\"title-loc-args\":[\"foo\",\"bar\"]}}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
     /// ```
-    pub fn set_title_loc_args<S>(mut self, args: &'a [S]) -> Self
+    pub fn set_title_loc_args<I>(mut self, args: I) -> Self
     where
-        S: Into<Cow<'a, str>> + AsRef<str>,
+        I: IntoIterator,
+        I::Item: Into<LocArg<'a>>,
     {
-        let converted = args.iter().map(|a| a.as_ref().into()).collect();
+        let converted = args.into_iter().map(Into::into).collect();
 
         self.alert.title_loc_args = Some(converted);
         self.has_edited_alert = true;
         self
     }
 
+    /// The localization key for the notification subtitle.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut builder = DefaultNotificationBuilder::new()
+    ///     .set_title("a title")
+    ///     .set_subtitle_loc_key("SENDER");
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"subtitle-loc-key\":\"SENDER\",\"title\":\"a title\"}}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_subtitle_loc_key<S>(mut self, key: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.alert.subtitle_loc_key = Some(key.into());
+        self.has_edited_alert = true;
+        self
+    }
+
+    /// Arguments for the subtitle localization.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut builder = DefaultNotificationBuilder::new()
+    ///     .set_title("a title")
+    ///     .set_subtitle_loc_args(&["foo", "bar"]);
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"subtitle-loc-args\":[\"foo\",\"bar\"],\"title\":\"a title\"}}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_subtitle_loc_args<I>(mut self, args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<LocArg<'a>>,
+    {
+        let converted = args.into_iter().map(Into::into).collect();
+
+        self.alert.subtitle_loc_args = Some(converted);
+        self.has_edited_alert = true;
+        self
+    }
+
     /// The localization key for the action.
     ///
     /// ```rust
@@ -298,13 +718,16 @@ impl<'a> DefaultNotificationBuilder<'a> {
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"alert\":{\"action-loc-key\":\"stop\",\"title\":\"a title\"},\"mutable-content\":0}}",
+    ///     "{\"aps\":{\"alert\":{\"action-loc-key\":\"stop\",\"title\":\"a title\"}}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
     /// ```
-    pub fn set_action_loc_key(mut self, key: &'a str) -> Self {
-        self.alert.action_loc_key = Some(key);
+    pub fn set_action_loc_key<S>(mut self, key: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.alert.action_loc_key = Some(key.into());
         self.has_edited_alert = true;
         self
     }
@@ -320,18 +743,23 @@ impl<'a> DefaultNotificationBuilder<'a> {
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"alert\":{\"loc-key\":\"lol\",\"title\":\"a title\"},\"mutable-content\":0}}",
+    ///     "{\"aps\":{\"alert\":{\"loc-key\":\"lol\",\"title\":\"a title\"}}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
     /// ```
-    pub fn set_loc_key(mut self, key: &'a str) -> Self {
-        self.alert.loc_key = Some(key);
+    pub fn set_loc_key<S>(mut self, key: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.alert.loc_key = Some(key.into());
         self.has_edited_alert = true;
         self
     }
 
-    /// Arguments for the content localization.
+    /// Arguments for the content localization, from any collection of
+    /// strings or numbers. Strings in a borrowed collection are borrowed,
+    /// not copied.
     ///
     /// ```rust
     /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
@@ -342,16 +770,17 @@ impl<'a> DefaultNotificationBuilder<'a> {
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"alert\":{\"loc-args\":[\"omg\",\"foo\"],\"title\":\"a title\"},\"mutable-content\":0}}",
+    ///     "{\"aps\":{\"alert\":{\"loc-args\":[\"omg\",\"foo\"],\"title\":\"a title\"}}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
     /// ```
-    pub fn set_loc_args<S>(mut self, args: &'a [S]) -> Self
+    pub fn set_loc_args<I>(mut self, args: I) -> Self
     where
-        S: Into<Cow<'a, str>> + AsRef<str>,
+        I: IntoIterator,
+        I::Item: Into<LocArg<'a>>,
     {
-        let converted = args.iter().map(|a| a.as_ref().into()).collect();
+        let converted = args.into_iter().map(Into::into).collect();
 
         self.alert.loc_args = Some(converted);
         self.has_edited_alert = true;
@@ -369,89 +798,432 @@ impl<'a> DefaultNotificationBuilder<'a> {
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"alert\":{\"launch-image\":\"cat.png\",\"title\":\"a title\"},\"mutable-content\":0}}",
+    ///     "{\"aps\":{\"alert\":{\"launch-image\":\"cat.png\",\"title\":\"a title\"}}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
     /// ```
-    pub fn set_launch_image(mut self, image: &'a str) -> Self {
-        self.alert.launch_image = Some(image);
+    pub fn set_launch_image<S>(mut self, image: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.alert.launch_image = Some(image.into());
         self.has_edited_alert = true;
         self
     }
 
-    /// Allow client to modify push content before displaying.
+    /// The string the system adds to the summary of grouped notifications,
+    /// usually naming the sender or topic of the notification.
     ///
     /// ```rust
     /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
     /// # fn main() {
     /// let mut builder = DefaultNotificationBuilder::new()
     ///     .set_title("a title")
-    ///     .set_mutable_content();
+    ///     .set_summary_arg("Anna");
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"mutable-content\":1}}",
+    ///     "{\"aps\":{\"alert\":{\"summary-arg\":\"Anna\",\"title\":\"a title\"}}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
     /// ```
-    pub fn set_mutable_content(mut self) -> Self {
-        self.mutable_content = 1;
+    pub fn set_summary_arg<S>(mut self, summary_arg: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.alert.summary_arg = Some(summary_arg.into());
+        self.has_edited_alert = true;
         self
     }
 
-    /// Used for adding custom data to push notifications
+    /// The number of items the notification adds to the summary of grouped
+    /// notifications. Counts as one if not set.
     ///
     /// ```rust
     /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
     /// # fn main() {
     /// let mut builder = DefaultNotificationBuilder::new()
     ///     .set_title("a title")
-    ///     .set_content_available();
+    ///     .set_summary_arg_count(3);
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"content-available\":1,\"mutable-content\":0}}",
+    ///     "{\"aps\":{\"alert\":{\"summary-arg-count\":3,\"title\":\"a title\"}}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
     /// ```
-    pub fn set_content_available(mut self) -> Self {
-        self.content_available = Some(1);
+    pub fn set_summary_arg_count(mut self, summary_arg_count: u32) -> Self {
+        self.alert.summary_arg_count = Some(summary_arg_count);
+        self.has_edited_alert = true;
         self
     }
-}
-
-impl<'a> NotificationBuilder<'a> for DefaultNotificationBuilder<'a> {
-    fn build(self, device_token: &'a str, options: NotificationOptions<'a>) -> Payload<'a> {
-        Payload {
-            aps: APS {
-                alert: match self.has_edited_alert {
-                    true => Some(APSAlert::Default(self.alert)),
-                    false => None,
-                },
-                badge: self.badge,
-                sound: self.sound,
-                content_available: self.content_available,
-                category: self.category,
-                mutable_content: Some(self.mutable_content),
-                url_args: None,
-            },
-            device_token,
-            options,
-            data: BTreeMap::new(),
-        }
-    }
-}
-
-impl<'a> Default for DefaultNotificationBuilder<'a> {
-    fn default() -> Self {
-        Self::new()
-    }
-}
 
-#[cfg(test)]
+    /// Allow client to modify push content before displaying. Needed for a
+    /// notification service extension to be invoked. Left out from the
+    /// payload if not set.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut builder = DefaultNotificationBuilder::new()
+    ///     .set_title("a title")
+    ///     .set_mutable_content();
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"mutable-content\":1}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_mutable_content(mut self) -> Self {
+        self.mutable_content = Some(1);
+        self
+    }
+
+    /// Makes the notification a critical alert, playing the given sound at a
+    /// volume clamped between 0.0 and 1.0 and setting the interruption level
+    /// to critical. Requires a special entitlement from Apple.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut builder = DefaultNotificationBuilder::new()
+    ///     .set_body("Smoke detected")
+    ///     .set_critical_alert("alarm.aiff", 0.8);
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"body\":\"Smoke detected\"},\"interruption-level\":\"critical\",\"sound\":{\"critical\":1,\"name\":\"alarm.aiff\",\"volume\":0.8}}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_critical_alert<S>(mut self, sound_name: S, volume: f64) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.sound = Some(APSSound::critical_clamped(sound_name, volume));
+        self.interruption_level = Some(InterruptionLevel::Critical);
+        self
+    }
+
+    /// The URL of an image or other media for a notification service extension
+    /// to download and attach. Sets `mutable-content` and adds the URL to the
+    /// custom data under the `media-url` key.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut builder = DefaultNotificationBuilder::new()
+    ///     .set_title("a title")
+    ///     .set_media_attachment("https://example.com/a.png");
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"mutable-content\":1},\"media-url\":\"https://example.com/a.png\"}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_media_attachment<S>(mut self, url: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.mutable_content = Some(1);
+        self.media_attachment = Some((Cow::Borrowed("media-url"), url.into()));
+        self
+    }
+
+    /// Like [set_media_attachment](#method.set_media_attachment), with the URL
+    /// under the given custom data key. Returns an error if the key is empty
+    /// or `aps`.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut builder = DefaultNotificationBuilder::new()
+    ///     .set_title("a title")
+    ///     .set_media_attachment_with_key("image", "https://example.com/a.png")
+    ///     .unwrap();
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"mutable-content\":1},\"image\":\"https://example.com/a.png\"}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_media_attachment_with_key<K, S>(mut self, key: K, url: S) -> Result<Self, Error>
+    where
+        K: Into<Cow<'a, str>>,
+        S: Into<Cow<'a, str>>,
+    {
+        let key = key.into();
+        check_custom_data_key(&key)?;
+
+        self.mutable_content = Some(1);
+        self.media_attachment = Some((key, url.into()));
+        Ok(self)
+    }
+
+    /// Marks the notification as a background update, waking the app to fetch
+    /// new content. Serialized as `content-available: 1`, and left out when
+    /// not called.
+    ///
+    /// Setting an alert, sound or badge as well is usually a mistake: the
+    /// notification is then delivered as a visible one, and it must be sent
+    /// with high priority. See
+    /// [Payload::validate](../payload/struct.Payload.html#method.validate).
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut builder = DefaultNotificationBuilder::new()
+    ///     .set_title("a title")
+    ///     .set_content_available();
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"content-available\":1}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_content_available(mut self) -> Self {
+        self.content_available = Some(1);
+        self
+    }
+
+    /// Sets or clears the background update flag, for when it depends on a
+    /// runtime value. `true` is the same as
+    /// [set_content_available](#method.set_content_available), `false` leaves
+    /// `content-available` out of the payload.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let has_new_mail = false;
+    /// let payload = DefaultNotificationBuilder::new()
+    ///     .set_title("a title")
+    ///     .content_available(has_new_mail)
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"}}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn content_available(mut self, content_available: bool) -> Self {
+        self.content_available = if content_available { Some(1) } else { None };
+        self
+    }
+
+    /// An identifier to group related notifications together in the
+    /// Notification Center.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut builder = DefaultNotificationBuilder::new()
+    ///     .set_title("a title")
+    ///     .set_thread_id("conversation-1");
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"thread-id\":\"conversation-1\"}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_thread_id<S>(mut self, thread_id: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.thread_id = Some(thread_id.into());
+        self
+    }
+
+    /// Groups the notification under `thread_id`, setting the `summary-arg`
+    /// and, if given, the `summary-arg-count` of the group summary at the
+    /// same time. The summary keys live in the alert dictionary, which this
+    /// builder always sends.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let builder = DefaultNotificationBuilder::new()
+    ///     .set_body("3 new messages")
+    ///     .set_group("conversation-1", "Anna", Some(3));
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"body\":\"3 new messages\",\"summary-arg\":\"Anna\",\"summary-arg-count\":3},\"thread-id\":\"conversation-1\"}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_group<T, S>(self, thread_id: T, summary_arg: S, summary_arg_count: Option<u32>) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+        S: Into<Cow<'a, str>>,
+    {
+        let builder = self.set_thread_id(thread_id).set_summary_arg(summary_arg);
+
+        match summary_arg_count {
+            Some(count) => builder.set_summary_arg_count(count),
+            None => builder,
+        }
+    }
+
+    /// The identifier of the window brought forward when the user opens the
+    /// notification. The value is free-form and defined by the app, matching
+    /// the `targetContentIdentifier` of the scene to activate.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut builder = DefaultNotificationBuilder::new()
+    ///     .set_title("a title")
+    ///     .set_target_content_id("inbox");
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"target-content-id\":\"inbox\"}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_target_content_id<S>(mut self, target_content_id: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.target_content_id = Some(target_content_id.into());
+        self
+    }
+
+    /// The importance and delivery timing of the notification, deciding if it
+    /// can break through Focus.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # use a2::request::payload::InterruptionLevel;
+    /// # fn main() {
+    /// let mut builder = DefaultNotificationBuilder::new()
+    ///     .set_title("a title")
+    ///     .set_interruption_level(InterruptionLevel::TimeSensitive);
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"interruption-level\":\"time-sensitive\"}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_interruption_level(mut self, interruption_level: InterruptionLevel) -> Self {
+        self.interruption_level = Some(interruption_level);
+        self
+    }
+
+    /// A score between 0.0 and 1.0 the system uses to sort the notifications
+    /// of the app in a notification summary. Returns an error if the score is
+    /// out of range.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut builder = DefaultNotificationBuilder::new()
+    ///     .set_title("a title")
+    ///     .set_relevance_score(0.8)
+    ///     .unwrap();
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"relevance-score\":0.8}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_relevance_score(mut self, relevance_score: f64) -> Result<Self, Error> {
+        if !(0.0..=1.0).contains(&relevance_score) {
+            return Err(PayloadError::InvalidRelevanceScore(relevance_score).into());
+        }
+
+        self.relevance_score = Some(relevance_score);
+        Ok(self)
+    }
+
+    /// The criteria the system evaluates to decide whether to show the
+    /// notification in the current Focus.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut builder = DefaultNotificationBuilder::new()
+    ///     .set_title("a title")
+    ///     .set_filter_criteria("work");
+    /// let payload = builder.build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"title\":\"a title\"},\"filter-criteria\":\"work\"}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_filter_criteria<S>(mut self, filter_criteria: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.filter_criteria = Some(filter_criteria.into());
+        self
+    }
+}
+
+impl<'a> NotificationBuilder<'a> for DefaultNotificationBuilder<'a> {
+    fn build(self, device_token: &'a str, options: NotificationOptions<'a>) -> Payload<'a> {
+        let mut data = BTreeMap::new();
+
+        if let Some((key, url)) = self.media_attachment {
+            data.insert(key, Value::from(url));
+        }
+
+        Payload {
+            aps: APS {
+                alert: match self.has_edited_alert {
+                    true => Some(APSAlert::Default(self.alert)),
+                    false => None,
+                },
+                badge: self.badge,
+                sound: self.sound,
+                content_available: self.content_available,
+                category: self.category,
+                mutable_content: self.mutable_content,
+                thread_id: self.thread_id,
+                target_content_id: self.target_content_id,
+                interruption_level: self.interruption_level,
+                relevance_score: self.relevance_score,
+                filter_criteria: self.filter_criteria,
+                ..Default::default()
+            },
+            device_token,
+            options,
+            data,
+            mdm: false,
+        }
+    }
+}
+
+impl<'a> Default for DefaultNotificationBuilder<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -469,8 +1241,7 @@ mod tests {
                 "alert": {
                     "title": "the title",
                     "body": "the body",
-                },
-                "mutable-content": 0
+                }
             }
         })
         .to_string();
@@ -493,7 +1264,9 @@ mod tests {
             .set_title_loc_key("STOP")
             .set_title_loc_args(&["herp", "derp"])
             .set_loc_key("PAUSE")
-            .set_loc_args(&["narf", "derp"]);
+            .set_loc_args(&["narf", "derp"])
+            .set_thread_id("thread-1")
+            .set_target_content_id("window-1");
 
         let payload = builder
             .build("device-token", Default::default())
@@ -515,7 +1288,9 @@ mod tests {
                 "badge": 420,
                 "category": "cat1",
                 "mutable-content": 1,
-                "sound": "prööt"
+                "sound": "prööt",
+                "target-content-id": "window-1",
+                "thread-id": "thread-1"
             }
         })
         .to_string();
@@ -565,8 +1340,7 @@ mod tests {
                 "alert": {
                     "title": "the title",
                     "body": "the body",
-                },
-                "mutable-content": 0,
+                }
             },
         })
         .to_string();
@@ -616,8 +1390,7 @@ mod tests {
             "aps": {
                 "alert": {
                     "body": "kulli"
-                },
-                "mutable-content": 0
+                }
             }
         })
         .to_string();
@@ -635,8 +1408,7 @@ mod tests {
 
         let expected_payload = json!({
             "aps": {
-                "content-available": 1,
-                "mutable-content": 0
+                "content-available": 1
             }
         })
         .to_string();
@@ -645,13 +1417,34 @@ mod tests {
     }
 
     #[test]
-    fn test_silent_notification_with_custom_data() {
-        #[derive(Serialize, Debug)]
-        struct SubData {
-            nothing: &'static str,
-        }
+    fn test_content_available_flag() {
+        let enabled = DefaultNotificationBuilder::new()
+            .content_available(true)
+            .build("device-token", Default::default());
 
-        #[derive(Serialize, Debug)]
+        assert_eq!(Some(1), enabled.aps.content_available);
+        assert_eq!(
+            json!({ "aps": { "content-available": 1 } }).to_string(),
+            enabled.to_json_string().unwrap()
+        );
+
+        let disabled = DefaultNotificationBuilder::new()
+            .set_content_available()
+            .content_available(false)
+            .build("device-token", Default::default());
+
+        assert_eq!(None, disabled.aps.content_available);
+        assert_eq!("{\"aps\":{}}", disabled.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_silent_notification_with_custom_data() {
+        #[derive(Serialize, Debug)]
+        struct SubData {
+            nothing: &'static str,
+        }
+
+        #[derive(Serialize, Debug)]
         struct TestData {
             key_str: &'static str,
             key_num: u32,
@@ -674,8 +1467,7 @@ mod tests {
 
         let expected_payload = json!({
             "aps": {
-                "content-available": 1,
-                "mutable-content": 0
+                "content-available": 1
             },
             "custom": {
                 "key_str": "foo",
@@ -705,8 +1497,7 @@ mod tests {
 
         let expected_payload = json!({
             "aps": {
-                "content-available": 1,
-                "mutable-content": 0,
+                "content-available": 1
             },
             "custom": {
                 "key_str": "foo",
@@ -717,4 +1508,784 @@ mod tests {
 
         assert_eq!(expected_payload, payload.to_json_string().unwrap());
     }
+
+    #[test]
+    fn test_mutable_content_with_localized_alert() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_loc_key("PAUSE")
+            .set_loc_args(&["narf"])
+            .set_mutable_content()
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "loc-key": "PAUSE",
+                    "loc-args": ["narf"]
+                },
+                "mutable-content": 1
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_thread_id_with_silent_notification() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_content_available()
+            .set_thread_id("chat-42")
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "content-available": 1,
+                "thread-id": "chat-42"
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_thread_id_omitted_when_not_set() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        assert!(!payload.contains("thread-id"));
+    }
+
+    #[test]
+    fn test_target_content_id_with_silent_notification() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_content_available()
+            .set_target_content_id("document-7")
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "content-available": 1,
+                "target-content-id": "document-7"
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_relevance_score() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .set_relevance_score(0.8)
+            .unwrap()
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "the body"
+                },
+                "relevance-score": 0.8
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_relevance_score_out_of_range() {
+        assert!(DefaultNotificationBuilder::new().set_relevance_score(1.1).is_err());
+        assert!(DefaultNotificationBuilder::new().set_relevance_score(-0.1).is_err());
+        assert!(DefaultNotificationBuilder::new().set_relevance_score(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_relevance_score_is_serialized_as_a_number() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_content_available()
+            .set_relevance_score(0.75)
+            .unwrap()
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&payload).unwrap();
+
+        assert_eq!(Some(0.75), value["aps"]["relevance-score"].as_f64());
+        assert_eq!("{\"aps\":{\"content-available\":1,\"relevance-score\":0.75}}", payload);
+    }
+
+    #[test]
+    fn test_filter_criteria_with_alert_and_silent_notifications() {
+        let alert_payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .set_filter_criteria("work")
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_alert_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "the body"
+                },
+                "filter-criteria": "work"
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_alert_payload, alert_payload);
+
+        let silent_payload = DefaultNotificationBuilder::new()
+            .set_content_available()
+            .set_filter_criteria("work")
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_silent_payload = json!({
+            "aps": {
+                "content-available": 1,
+                "filter-criteria": "work"
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_silent_payload, silent_payload);
+    }
+
+    #[test]
+    fn test_notification_with_critical_sound() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .set_sound(APSSound::critical("alarm.aiff", 0.5).unwrap())
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "the body"
+                },
+                "sound": {
+                    "critical": 1,
+                    "name": "alarm.aiff",
+                    "volume": 0.5
+                }
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_loc_args_with_numbers() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_loc_key("SCORE")
+            .set_loc_args(&[LocArg::from("Tigers"), LocArg::from(3), LocArg::from(0.5)])
+            .set_title_loc_args(&[42])
+            .set_subtitle_loc_args([String::from("owned")])
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "loc-key": "SCORE",
+                    "loc-args": ["Tigers", 3, 0.5],
+                    "subtitle-loc-args": ["owned"],
+                    "title-loc-args": [42]
+                }
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_media_attachment_with_category_and_thread_id() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_title("New photo")
+            .set_body("Anna shared a photo")
+            .set_category("photo")
+            .set_thread_id("album-42")
+            .set_media_attachment("https://example.com/photo.jpg")
+            .build("device-token", Default::default());
+
+        assert!(payload.validate().is_ok());
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "title": "New photo",
+                    "body": "Anna shared a photo"
+                },
+                "category": "photo",
+                "mutable-content": 1,
+                "thread-id": "album-42"
+            },
+            "media-url": "https://example.com/photo.jpg"
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_media_attachment_without_alert() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_content_available()
+            .set_media_attachment("https://example.com/photo.jpg")
+            .build("device-token", Default::default());
+
+        assert!(payload.validate().is_err());
+    }
+
+    #[test]
+    fn test_media_attachment_with_reserved_key() {
+        match DefaultNotificationBuilder::new().set_media_attachment_with_key("aps", "https://example.com/a.png") {
+            Err(Error::PayloadError(PayloadError::ReservedKey(key))) => assert_eq!("aps", key),
+            other => panic!("expected a reserved key error, got {:?}", other),
+        }
+
+        assert!(DefaultNotificationBuilder::new()
+            .set_media_attachment_with_key("", "https://example.com/a.png")
+            .is_err());
+    }
+
+    #[test]
+    fn test_format_argument_count() {
+        assert_eq!(0, format_argument_count("Hello"));
+        assert_eq!(3, format_argument_count("%@ and %@ and %@"));
+        assert_eq!(2, format_argument_count("%2$@ before %1$@"));
+        assert_eq!(1, format_argument_count("%1$@ and again %1$@"));
+        assert_eq!(3, format_argument_count("only %3$@"));
+        assert_eq!(1, format_argument_count("100%% done in %d seconds"));
+        assert_eq!(3, format_argument_count("%@ %ld %5.2f"));
+    }
+
+    #[test]
+    fn test_loc_args_borrow_from_slices() {
+        let names = vec![String::from("Anna"), String::from("Frank")];
+        let refs: Vec<&String> = names.iter().collect();
+        let args = vec![LocArg::from(names[0].as_str()), LocArg::from(3)];
+
+        let payload = DefaultNotificationBuilder::new()
+            .set_title_loc_args(&names)
+            .set_subtitle_loc_args(&refs[..])
+            .set_loc_args(&args)
+            .build("device-token", Default::default());
+
+        let alert = match payload.aps.alert {
+            Some(APSAlert::Default(alert)) => alert,
+            other => panic!("expected a default alert, got {:?}", other),
+        };
+
+        for args in [&alert.title_loc_args, &alert.subtitle_loc_args] {
+            assert_eq!(
+                &Some(vec![
+                    LocArg::Str(Cow::Borrowed("Anna")),
+                    LocArg::Str(Cow::Borrowed("Frank"))
+                ]),
+                args
+            );
+        }
+
+        assert!(matches!(
+            alert.loc_args.as_deref(),
+            Some([LocArg::Str(Cow::Borrowed("Anna")), LocArg::Int(3)])
+        ));
+        assert!(alert
+            .title_loc_args
+            .iter()
+            .chain(&alert.subtitle_loc_args)
+            .flatten()
+            .all(|arg| matches!(arg, LocArg::Str(Cow::Borrowed(_)))));
+    }
+
+    #[test]
+    fn test_validate_loc_args_against_templates() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_title_loc_key("TITLE")
+            .set_title_loc_args(&["Anna"])
+            .set_loc_key("BODY")
+            .set_loc_args([LocArg::from("Anna"), LocArg::from(3)])
+            .build("device-token", Default::default());
+
+        let alert = match payload.aps.alert {
+            Some(APSAlert::Default(alert)) => alert,
+            other => panic!("expected a default alert, got {:?}", other),
+        };
+
+        assert!(alert.validate_title_loc_args("Message from %@").is_ok());
+        assert!(alert.validate_loc_args("%1$@ sent you %2$d photos").is_ok());
+        assert!(alert.validate_loc_args("%@ sent you photos").is_err());
+        assert!(alert.validate_loc_args("%@ sent you %d photos in %@").is_err());
+        assert!(alert.validate_subtitle_loc_args("No arguments").is_ok());
+        assert!(alert.validate_subtitle_loc_args("%@").is_err());
+    }
+
+    #[test]
+    fn test_set_critical_sound() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .set_critical_sound("alarm.aiff", 1.0)
+            .unwrap()
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "the body"
+                },
+                "sound": {
+                    "critical": 1,
+                    "name": "alarm.aiff",
+                    "volume": 1.0
+                }
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+        assert!(DefaultNotificationBuilder::new()
+            .set_critical_sound("alarm.aiff", -0.1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_critical_alert() {
+        // The critical alert sound example in Apple's "Generating a remote
+        // notification" documentation, with the interruption level added.
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "Smoke detected in the kitchen"
+                },
+                "interruption-level": "critical",
+                "sound": {
+                    "critical": 1,
+                    "name": "your_custom_sound.aiff",
+                    "volume": 1.0
+                }
+            }
+        })
+        .to_string();
+
+        for volume in [1.0, 2.5, f64::NAN] {
+            let payload = DefaultNotificationBuilder::new()
+                .set_body("Smoke detected in the kitchen")
+                .set_critical_alert("your_custom_sound.aiff", volume)
+                .build("device-token", Default::default())
+                .to_json_string()
+                .unwrap();
+
+            assert_eq!(expected_payload, payload);
+        }
+
+        let payload = DefaultNotificationBuilder::new()
+            .set_body("Smoke detected in the kitchen")
+            .set_critical_alert("your_custom_sound.aiff", -1.0)
+            .build("device-token", Default::default());
+
+        assert_eq!(
+            Some(APSSound::critical("your_custom_sound.aiff", 0.0).unwrap()),
+            payload.aps.sound
+        );
+    }
+
+    #[test]
+    fn test_alert_with_title_subtitle_and_body() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_title("the title")
+            .set_subtitle("the subtitle")
+            .set_body("the body")
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "title": "the title",
+                    "subtitle": "the subtitle",
+                    "body": "the body"
+                }
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+
+        let without_subtitle = DefaultNotificationBuilder::new()
+            .set_title("the title")
+            .set_body("the body")
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        assert!(!without_subtitle.contains("subtitle"));
+    }
+
+    #[test]
+    fn test_localized_subtitle() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_title("the title")
+            .set_subtitle_loc_key("FROM_SENDER")
+            .set_subtitle_loc_args(&["first", "second", "third"])
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "subtitle-loc-args": ["first", "second", "third"],
+                    "subtitle-loc-key": "FROM_SENDER",
+                    "title": "the title"
+                }
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_grouped_notification_summary() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_title("Anna")
+            .set_body("Three new photos")
+            .set_thread_id("chat-anna")
+            .set_summary_arg("Anna")
+            .set_summary_arg_count(3)
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "Three new photos",
+                    "summary-arg": "Anna",
+                    "summary-arg-count": 3,
+                    "title": "Anna"
+                },
+                "thread-id": "chat-anna"
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_badge_zero_and_unset_badge() {
+        let cleared = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .set_badge(0)
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "the body"
+                },
+                "badge": 0
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, cleared);
+
+        let unchanged = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        assert!(!unchanged.contains("badge"));
+    }
+
+    #[test]
+    fn test_payload_from_literals_borrows_every_string() {
+        let payload: Payload<'static> = DefaultNotificationBuilder::new()
+            .set_title("title")
+            .set_subtitle("subtitle")
+            .set_body("body")
+            .set_title_loc_key("TITLE")
+            .set_subtitle_loc_key("SUBTITLE")
+            .set_action_loc_key("PLAY")
+            .set_loc_key("BODY")
+            .set_loc_args(&["arg"])
+            .set_launch_image("image.png")
+            .set_summary_arg("summary")
+            .build("token", Default::default());
+
+        let alert = match payload.aps.alert {
+            Some(APSAlert::Default(ref alert)) => alert,
+            ref other => panic!("expected a default alert, got {:?}", other),
+        };
+
+        let texts = [
+            &alert.title,
+            &alert.subtitle,
+            &alert.body,
+            &alert.title_loc_key,
+            &alert.subtitle_loc_key,
+            &alert.action_loc_key,
+            &alert.loc_key,
+            &alert.launch_image,
+            &alert.summary_arg,
+        ];
+
+        for text in texts {
+            assert!(matches!(text, Some(Cow::Borrowed(_))), "{:?}", text);
+        }
+
+        assert!(matches!(
+            alert.loc_args.as_deref(),
+            Some([LocArg::Str(Cow::Borrowed("arg"))])
+        ));
+    }
+
+    #[test]
+    fn test_owned_alert_texts_match_borrowed() {
+        let borrowed = DefaultNotificationBuilder::new()
+            .set_title("Hi Anna")
+            .set_subtitle("2 new messages")
+            .set_body("See you at 8")
+            .build("token", Default::default());
+
+        let name = "Anna";
+        let owned = DefaultNotificationBuilder::new()
+            .set_title(format!("Hi {}", name))
+            .set_subtitle(format!("{} new messages", 2))
+            .set_body(String::from("See you at 8"))
+            .build("token", Default::default());
+
+        assert_eq!(borrowed, owned);
+        assert_eq!(borrowed.to_json_string().unwrap(), owned.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_builder_with_title_and_body() {
+        let payload = DefaultNotificationBuilder::with_title_and_body("Game", String::from("Your turn"))
+            .set_category("GAME")
+            .set_mutable_content()
+            .set_thread_id("game-42")
+            .build("token", Default::default());
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "title": "Game",
+                    "body": "Your turn",
+                },
+                "category": "GAME",
+                "mutable-content": 1,
+                "thread-id": "game-42",
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+
+        let minimal =
+            DefaultNotificationBuilder::with_title_and_body("Game", "Your turn").build("token", Default::default());
+
+        assert_eq!(
+            "{\"aps\":{\"alert\":{\"body\":\"Your turn\",\"title\":\"Game\"}}}",
+            minimal.to_json_string().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_clear_badge_versus_unset_badge() {
+        let cleared = DefaultNotificationBuilder::new()
+            .set_body("read")
+            .set_badge(5)
+            .clear_badge()
+            .build("token", Default::default());
+
+        assert_eq!(Some(0), cleared.aps.badge);
+        assert_eq!(
+            "{\"aps\":{\"alert\":{\"body\":\"read\"},\"badge\":0}}",
+            cleared.to_json_string().unwrap()
+        );
+
+        let unchanged = DefaultNotificationBuilder::new()
+            .set_body("read")
+            .build("token", Default::default());
+
+        assert_eq!(None, unchanged.aps.badge);
+        assert_eq!(
+            "{\"aps\":{\"alert\":{\"body\":\"read\"}}}",
+            unchanged.to_json_string().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_set_group() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_title("Anna")
+            .set_body("See you at 8")
+            .set_group("chat-anna", "Anna", None)
+            .build("token", Default::default());
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "See you at 8",
+                    "summary-arg": "Anna",
+                    "title": "Anna",
+                },
+                "thread-id": "chat-anna",
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+
+        let payload = DefaultNotificationBuilder::new()
+            .set_body("2 new photos")
+            .set_group("album-7", "Holiday", Some(2))
+            .build("token", Default::default());
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "2 new photos",
+                    "summary-arg": "Holiday",
+                    "summary-arg-count": 2,
+                },
+                "thread-id": "album-7",
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_setters_mix_borrowed_and_owned_strings() {
+        let sender = String::from("Anna");
+
+        let mut payload = DefaultNotificationBuilder::with_title_and_body("New message", format!("From {}", sender))
+            .set_subtitle(Cow::Borrowed("Chat"))
+            .set_title_loc_key(String::from("TITLE"))
+            .set_subtitle_loc_key("SUBTITLE")
+            .set_action_loc_key(Cow::Owned(String::from("REPLY")))
+            .set_loc_key("BODY")
+            .set_launch_image(String::from("chat.png"))
+            .set_group("chat-anna", sender.clone(), Some(2))
+            .set_media_attachment_with_key(
+                String::from("avatar-url"),
+                format!("https://example.com/{}.png", sender),
+            )
+            .unwrap()
+            .build("token", Default::default());
+
+        payload.add_custom_data(format!("chat-{}", 42), &sender).unwrap();
+        payload.add_aps_data(String::from("sender-id"), &7).unwrap();
+
+        let expected_payload = json!({
+            "avatar-url": "https://example.com/Anna.png",
+            "chat-42": "Anna",
+            "aps": {
+                "alert": {
+                    "action-loc-key": "REPLY",
+                    "body": "From Anna",
+                    "launch-image": "chat.png",
+                    "loc-key": "BODY",
+                    "subtitle": "Chat",
+                    "subtitle-loc-key": "SUBTITLE",
+                    "summary-arg": "Anna",
+                    "summary-arg-count": 2,
+                    "title": "New message",
+                    "title-loc-key": "TITLE",
+                },
+                "mutable-content": 1,
+                "sender-id": 7,
+                "thread-id": "chat-anna",
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_set_media_attachment_takes_owned_url() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_body("New photo")
+            .set_media_attachment(format!("https://example.com/{}.jpg", 42))
+            .build("token", Default::default());
+
+        assert_eq!(
+            Some(&json!("https://example.com/42.jpg")),
+            payload.data.get("media-url")
+        );
+    }
+
+    #[test]
+    fn test_aps_setters_take_owned_strings() {
+        let chat_id = 42;
+
+        let payload = DefaultNotificationBuilder::new()
+            .set_body("New message")
+            .set_category(String::from("MESSAGE"))
+            .set_group(format!("chat-{}", chat_id), "Anna", None)
+            .set_target_content_id(format!("window-{}", chat_id))
+            .set_filter_criteria(Cow::Borrowed("work"))
+            .set_sound(String::from("ping.aiff"))
+            .build("token", Default::default());
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "New message",
+                    "summary-arg": "Anna",
+                },
+                "category": "MESSAGE",
+                "filter-criteria": "work",
+                "sound": "ping.aiff",
+                "target-content-id": "window-42",
+                "thread-id": "chat-42",
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+
+        let critical = DefaultNotificationBuilder::new()
+            .set_body("Smoke detected")
+            .set_critical_alert(String::from("alarm.aiff"), 0.5)
+            .build("token", Default::default());
+
+        assert_eq!(Some(APSSound::critical("alarm.aiff", 0.5).unwrap()), critical.aps.sound);
+    }
 }
@@ -0,0 +1,692 @@
+use crate::error::Error;
+use crate::request::notification::{DefaultAlert, NotificationBuilder, NotificationOptions};
+use crate::request::payload::{APSAlert, Payload, PayloadError, APS};
+use erased_serde::Serialize;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The action a Live Activity notification performs.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LiveActivityEvent {
+    /// Starts a new Live Activity.
+    Start,
+    /// Updates the content of a running Live Activity.
+    Update,
+    /// Ends a running Live Activity.
+    End,
+}
+
+/// The UNIX time in seconds when the content of a Live Activity was produced.
+/// The device discards notifications older than the content it shows.
+///
+/// ```rust
+/// # use a2::request::notification::LiveActivityTimestamp;
+/// # use std::time::{Duration, UNIX_EPOCH};
+/// # fn main() {
+/// let timestamp = LiveActivityTimestamp::try_from(UNIX_EPOCH + Duration::from_millis(1685952000750)).unwrap();
+///
+/// assert_eq!(1685952000, timestamp.as_secs());
+/// assert_eq!(1685952000, LiveActivityTimestamp::from(1685952000).as_secs());
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LiveActivityTimestamp(u64);
+
+impl LiveActivityTimestamp {
+    /// The current system time.
+    pub fn now() -> LiveActivityTimestamp {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs();
+
+        LiveActivityTimestamp(timestamp)
+    }
+
+    /// The timestamp in whole seconds since the UNIX epoch.
+    pub fn as_secs(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for LiveActivityTimestamp {
+    fn from(secs: u64) -> Self {
+        LiveActivityTimestamp(secs)
+    }
+}
+
+impl TryFrom<SystemTime> for LiveActivityTimestamp {
+    type Error = Error;
+
+    fn try_from(time: SystemTime) -> Result<Self, Self::Error> {
+        unix_seconds(time, "timestamp").map(LiveActivityTimestamp)
+    }
+}
+
+/// A builder to create a Live Activity notification payload.
+///
+/// # Example
+///
+/// ```rust
+/// # use a2::request::notification::{LiveActivityEvent, LiveActivityNotificationBuilder, NotificationBuilder};
+/// # use std::collections::HashMap;
+/// # fn main() {
+/// let mut content_state = HashMap::new();
+/// content_state.insert("score", "2-1");
+///
+/// let payload = LiveActivityNotificationBuilder::new(LiveActivityEvent::Update, 1685952000)
+///     .set_content_state(&content_state)
+///     .unwrap()
+///     .build("device_id", Default::default())
+///     .to_json_string()
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LiveActivityNotificationBuilder<'a> {
+    event: LiveActivityEvent,
+    timestamp: u64,
+    alert: Option<DefaultAlert<'a>>,
+    content_state: Option<Value>,
+    attributes_type: Option<Cow<'a, str>>,
+    attributes: Option<Value>,
+    input_push_token: Option<u8>,
+    stale_date: Option<u64>,
+    dismissal_date: Option<u64>,
+}
+
+impl<'a> LiveActivityNotificationBuilder<'a> {
+    /// Creates a new builder for the given event. The `timestamp` is the UNIX
+    /// time in seconds when the content was produced, letting the device
+    /// discard updates arriving out of order. See
+    /// [LiveActivityTimestamp](struct.LiveActivityTimestamp.html) for creating
+    /// one from a `SystemTime`.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LiveActivityEvent, LiveActivityNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let payload = LiveActivityNotificationBuilder::new(LiveActivityEvent::End, 1685952000)
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"event\":\"end\",\"timestamp\":1685952000}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn new<T>(event: LiveActivityEvent, timestamp: T) -> LiveActivityNotificationBuilder<'a>
+    where
+        T: Into<LiveActivityTimestamp>,
+    {
+        LiveActivityNotificationBuilder {
+            event,
+            timestamp: timestamp.into().as_secs(),
+            alert: None,
+            content_state: None,
+            attributes_type: None,
+            attributes: None,
+            input_push_token: None,
+            stale_date: None,
+            dismissal_date: None,
+        }
+    }
+
+    /// Creates a new builder to start a Live Activity remotely. The
+    /// `attributes_type` is the name of the activity attributes struct in the
+    /// app, and `attributes` its static data. APNs also requires an alert
+    /// for starting, set with [set_alert](#method.set_alert).
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LiveActivityNotificationBuilder, NotificationBuilder};
+    /// # use std::collections::HashMap;
+    /// # fn main() {
+    /// let mut attributes = HashMap::new();
+    /// attributes.insert("home_team", "Tigers");
+    ///
+    /// let payload = LiveActivityNotificationBuilder::start(1685952000, "MatchAttributes", &attributes)
+    ///     .unwrap()
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"attributes\":{\"home_team\":\"Tigers\"},\"attributes-type\":\"MatchAttributes\",\"event\":\"start\",\"timestamp\":1685952000}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn start<T, S>(
+        timestamp: T,
+        attributes_type: S,
+        attributes: &dyn Serialize,
+    ) -> Result<LiveActivityNotificationBuilder<'a>, Error>
+    where
+        T: Into<LiveActivityTimestamp>,
+        S: Into<Cow<'a, str>>,
+    {
+        let mut builder = Self::new(LiveActivityEvent::Start, timestamp).set_attributes(attributes)?;
+        builder.attributes_type = Some(attributes_type.into());
+
+        Ok(builder)
+    }
+
+    /// Creates a new builder for the given event, using the current system
+    /// time as the timestamp.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LiveActivityEvent, LiveActivityNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let payload = LiveActivityNotificationBuilder::now(LiveActivityEvent::Update)
+    ///     .build("token", Default::default());
+    ///
+    /// assert!(payload.aps.timestamp.is_some());
+    /// # }
+    /// ```
+    pub fn now(event: LiveActivityEvent) -> LiveActivityNotificationBuilder<'a> {
+        Self::new(event, LiveActivityTimestamp::now())
+    }
+
+    /// The dynamic content of the Live Activity. The `content_state` should
+    /// match the `ContentState` of the activity attributes in the app.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LiveActivityEvent, LiveActivityNotificationBuilder, NotificationBuilder};
+    /// # use std::collections::HashMap;
+    /// # fn main() {
+    /// let mut content_state = HashMap::new();
+    /// content_state.insert("score", "2-1");
+    ///
+    /// let payload = LiveActivityNotificationBuilder::new(LiveActivityEvent::Update, 1685952000)
+    ///     .set_content_state(&content_state)
+    ///     .unwrap()
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"content-state\":{\"score\":\"2-1\"},\"event\":\"update\",\"timestamp\":1685952000}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_content_state(mut self, content_state: &dyn Serialize) -> Result<Self, Error> {
+        self.content_state = Some(serde_json::to_value(content_state)?);
+        Ok(self)
+    }
+
+    /// The alert shown on the device when the Live Activity starts, updates or
+    /// ends. Required when starting a Live Activity.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LiveActivityEvent, LiveActivityNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let payload = LiveActivityNotificationBuilder::new(LiveActivityEvent::End, 1685952000)
+    ///     .set_alert("Final score", "Tigers won 3-1")
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"body\":\"Tigers won 3-1\",\"title\":\"Final score\"},\"event\":\"end\",\"timestamp\":1685952000}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_alert<T, B>(mut self, title: T, body: B) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+        B: Into<Cow<'a, str>>,
+    {
+        self.alert = Some(DefaultAlert::with_title_and_body(title.into(), body.into()));
+        self
+    }
+
+    /// The static attributes of the Live Activity when starting it with a
+    /// notification, matching the activity attributes in the app. See also
+    /// [start](#method.start), which sets the attributes type as well.
+    /// Returns an error if the event is not `Start`.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LiveActivityEvent, LiveActivityNotificationBuilder, NotificationBuilder};
+    /// # use std::collections::HashMap;
+    /// # fn main() {
+    /// let mut attributes = HashMap::new();
+    /// attributes.insert("home_team", "Tigers");
+    ///
+    /// let payload = LiveActivityNotificationBuilder::new(LiveActivityEvent::Start, 1685952000)
+    ///     .set_attributes(&attributes)
+    ///     .unwrap()
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"attributes\":{\"home_team\":\"Tigers\"},\"event\":\"start\",\"timestamp\":1685952000}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_attributes(mut self, attributes: &dyn Serialize) -> Result<Self, Error> {
+        if self.event != LiveActivityEvent::Start {
+            return Err(
+                PayloadError::ConflictingKeys("attributes can only be set when starting a Live Activity").into(),
+            );
+        }
+
+        self.attributes = Some(serde_json::to_value(attributes)?);
+        Ok(self)
+    }
+
+    /// Requests the device to report a push token for the started Live
+    /// Activity. Only valid when starting a Live Activity, returns an error
+    /// for other events.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LiveActivityEvent, LiveActivityNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let payload = LiveActivityNotificationBuilder::new(LiveActivityEvent::Start, 1685952000)
+    ///     .set_input_push_token()
+    ///     .unwrap()
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"event\":\"start\",\"input-push-token\":1,\"timestamp\":1685952000}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_input_push_token(mut self) -> Result<Self, Error> {
+        if self.event != LiveActivityEvent::Start {
+            return Err(PayloadError::ConflictingKeys(
+                "input-push-token can only be set when starting a Live Activity",
+            )
+            .into());
+        }
+
+        self.input_push_token = Some(1);
+        Ok(self)
+    }
+
+    /// The time after which the content is considered outdated and the system
+    /// shows the Live Activity as stale.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LiveActivityEvent, LiveActivityNotificationBuilder, NotificationBuilder};
+    /// # use std::time::{Duration, UNIX_EPOCH};
+    /// # fn main() {
+    /// let payload = LiveActivityNotificationBuilder::new(LiveActivityEvent::Update, 1685952000)
+    ///     .set_stale_date(UNIX_EPOCH + Duration::from_secs(1685955600))
+    ///     .unwrap()
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"event\":\"update\",\"stale-date\":1685955600,\"timestamp\":1685952000}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_stale_date(mut self, stale_date: SystemTime) -> Result<Self, Error> {
+        self.stale_date = Some(unix_seconds(stale_date, "stale date")?);
+        Ok(self)
+    }
+
+    /// The time when an ended Live Activity is removed from the Lock Screen.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LiveActivityEvent, LiveActivityNotificationBuilder, NotificationBuilder};
+    /// # use std::time::{Duration, UNIX_EPOCH};
+    /// # fn main() {
+    /// let payload = LiveActivityNotificationBuilder::new(LiveActivityEvent::End, 1685952000)
+    ///     .set_dismissal_date(UNIX_EPOCH + Duration::from_secs(1685959200))
+    ///     .unwrap()
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"dismissal-date\":1685959200,\"event\":\"end\",\"timestamp\":1685952000}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_dismissal_date(mut self, dismissal_date: SystemTime) -> Result<Self, Error> {
+        self.dismissal_date = Some(unix_seconds(dismissal_date, "dismissal date")?);
+        Ok(self)
+    }
+}
+
+impl<'a> NotificationBuilder<'a> for LiveActivityNotificationBuilder<'a> {
+    fn build(self, device_token: &'a str, options: NotificationOptions<'a>) -> Payload<'a> {
+        Payload {
+            aps: APS {
+                alert: self.alert.map(APSAlert::Default),
+                event: Some(self.event),
+                timestamp: Some(self.timestamp),
+                content_state: self.content_state,
+                attributes_type: self.attributes_type,
+                attributes: self.attributes,
+                input_push_token: self.input_push_token,
+                stale_date: self.stale_date,
+                dismissal_date: self.dismissal_date,
+                ..Default::default()
+            },
+            device_token,
+            options,
+            data: BTreeMap::new(),
+            mdm: false,
+        }
+    }
+}
+
+fn unix_seconds(time: SystemTime, field: &'static str) -> Result<u64, Error> {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .map_err(|_| PayloadError::DateBeforeUnixEpoch(field).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_activity_update_notification() {
+        #[derive(Serialize, Debug)]
+        struct ContentState {
+            current_health_level: f64,
+            event_description: &'static str,
+        }
+
+        let content_state = ContentState {
+            current_health_level: 0.5,
+            event_description: "Power Panda found a sword!",
+        };
+
+        let payload = LiveActivityNotificationBuilder::new(LiveActivityEvent::Update, 1685952000)
+            .set_content_state(&content_state)
+            .unwrap()
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "content-state": {
+                    "current_health_level": 0.5,
+                    "event_description": "Power Panda found a sword!"
+                },
+                "event": "update",
+                "timestamp": 1685952000
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_live_activity_events() {
+        let events = vec![
+            (LiveActivityEvent::Start, "start"),
+            (LiveActivityEvent::Update, "update"),
+            (LiveActivityEvent::End, "end"),
+        ];
+
+        for (event, expected) in events.into_iter() {
+            assert_eq!(json!(expected), serde_json::to_value(event).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_live_activity_with_nested_content_state() {
+        let content_state = json!({
+            "driver": {
+                "name": "Anna",
+                "location": [60.17, 24.94]
+            },
+            "stops": [
+                {"name": "Market square", "eta": 5},
+                {"name": "Harbour", "eta": 12}
+            ]
+        });
+
+        let payload = LiveActivityNotificationBuilder::new(LiveActivityEvent::Update, 1685952000)
+            .set_content_state(&content_state)
+            .unwrap()
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "content-state": content_state,
+                "event": "update",
+                "timestamp": 1685952000
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_live_activity_timestamp_from_current_time() {
+        let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let payload =
+            LiveActivityNotificationBuilder::now(LiveActivityEvent::Update).build("device-token", Default::default());
+        let after = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+        let timestamp = payload.aps.timestamp.unwrap();
+
+        assert!(before <= timestamp && timestamp <= after);
+    }
+
+    #[test]
+    fn test_live_activity_dates_before_epoch() {
+        let before_epoch = UNIX_EPOCH - std::time::Duration::from_secs(1);
+
+        assert!(
+            LiveActivityNotificationBuilder::new(LiveActivityEvent::Update, 1685952000)
+                .set_stale_date(before_epoch)
+                .is_err()
+        );
+
+        assert!(LiveActivityNotificationBuilder::new(LiveActivityEvent::End, 1685952000)
+            .set_dismissal_date(before_epoch)
+            .is_err());
+    }
+
+    #[test]
+    fn test_live_activity_update_with_stale_date() {
+        let payload = LiveActivityNotificationBuilder::new(LiveActivityEvent::Update, 1685952000)
+            .set_content_state(&json!({"score": "2-1"}))
+            .unwrap()
+            .set_stale_date(UNIX_EPOCH + std::time::Duration::from_secs(1685955600))
+            .unwrap()
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "content-state": {
+                    "score": "2-1"
+                },
+                "event": "update",
+                "stale-date": 1685955600,
+                "timestamp": 1685952000
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_live_activity_end_with_dismissal_date() {
+        let payload = LiveActivityNotificationBuilder::new(LiveActivityEvent::End, 1685952000)
+            .set_content_state(&json!({"score": "3-1"}))
+            .unwrap()
+            .set_dismissal_date(UNIX_EPOCH + std::time::Duration::from_secs(1685959200))
+            .unwrap()
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "content-state": {
+                    "score": "3-1"
+                },
+                "dismissal-date": 1685959200,
+                "event": "end",
+                "timestamp": 1685952000
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_live_activity_start_with_attributes() {
+        let payload = LiveActivityNotificationBuilder::new(LiveActivityEvent::Start, 1685952000)
+            .set_attributes(&json!({"home_team": "Tigers", "away_team": "Lions"}))
+            .unwrap()
+            .set_content_state(&json!({"score": "0-0"}))
+            .unwrap()
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "attributes": {
+                    "away_team": "Lions",
+                    "home_team": "Tigers"
+                },
+                "content-state": {
+                    "score": "0-0"
+                },
+                "event": "start",
+                "timestamp": 1685952000
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_live_activity_start() {
+        let payload =
+            LiveActivityNotificationBuilder::start(1685952000, "MatchAttributes", &json!({"home_team": "Tigers"}))
+                .unwrap()
+                .set_content_state(&json!({"score": "0-0"}))
+                .unwrap()
+                .build("device-token", Default::default())
+                .to_json_string()
+                .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "attributes": {
+                    "home_team": "Tigers"
+                },
+                "attributes-type": "MatchAttributes",
+                "content-state": {
+                    "score": "0-0"
+                },
+                "event": "start",
+                "timestamp": 1685952000
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_live_activity_start_with_alert() {
+        let payload =
+            LiveActivityNotificationBuilder::start(1685952000, "MatchAttributes", &json!({"home_team": "Tigers"}))
+                .unwrap()
+                .set_content_state(&json!({"score": "0-0"}))
+                .unwrap()
+                .set_alert("Kick-off", format!("{} vs {} has started", "Tigers", "Lions"))
+                .build("device-token", Default::default());
+
+        assert!(payload.validate().is_ok());
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "title": "Kick-off",
+                    "body": "Tigers vs Lions has started"
+                },
+                "attributes": {
+                    "home_team": "Tigers"
+                },
+                "attributes-type": "MatchAttributes",
+                "content-state": {
+                    "score": "0-0"
+                },
+                "event": "start",
+                "timestamp": 1685952000
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_live_activity_start_without_alert() {
+        let payload =
+            LiveActivityNotificationBuilder::start(1685952000, "MatchAttributes", &json!({"home_team": "Tigers"}))
+                .unwrap()
+                .build("device-token", Default::default());
+
+        assert!(payload.validate().is_err());
+    }
+
+    #[test]
+    fn test_live_activity_attributes_outside_start() {
+        for event in [LiveActivityEvent::Update, LiveActivityEvent::End] {
+            assert!(LiveActivityNotificationBuilder::new(event, 1685952000)
+                .set_attributes(&json!({"home_team": "Tigers"}))
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_live_activity_input_push_token() {
+        let payload = LiveActivityNotificationBuilder::start(1685952000, "MatchAttributes", &json!({}))
+            .unwrap()
+            .set_input_push_token()
+            .unwrap()
+            .build("device-token", Default::default());
+
+        assert_eq!(Some(1), payload.aps.input_push_token);
+
+        for event in [LiveActivityEvent::Update, LiveActivityEvent::End] {
+            assert!(LiveActivityNotificationBuilder::new(event, 1685952000)
+                .set_input_push_token()
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_live_activity_timestamp_from_system_time() {
+        let time = UNIX_EPOCH + std::time::Duration::from_millis(1685952000999);
+        let timestamp = LiveActivityTimestamp::try_from(time).unwrap();
+
+        let payload = LiveActivityNotificationBuilder::new(LiveActivityEvent::Update, timestamp)
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "event": "update",
+                "timestamp": 1685952000
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+        assert!(LiveActivityTimestamp::try_from(UNIX_EPOCH - std::time::Duration::from_secs(1)).is_err());
+    }
+}
@@ -0,0 +1,313 @@
+use crate::request::notification::{DefaultNotificationBuilder, LocArg, NotificationBuilder, NotificationOptions};
+use crate::request::payload::{APSSound, Payload};
+use std::borrow::Cow;
+
+/// A builder to create a notification shown from a localized string in the
+/// app bundle, with the arguments substituted on the device.
+///
+/// # Example
+///
+/// ```rust
+/// # use a2::request::notification::{LocalizedNotificationBuilder, NotificationBuilder};
+/// # fn main() {
+/// let payload = LocalizedNotificationBuilder::new("GAME_PLAY_REQUEST_FORMAT", ["Jenna", "Frank"])
+///     .set_badge(1)
+///     .build("device_id", Default::default())
+///     .to_json_string()
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocalizedNotificationBuilder<'a> {
+    builder: DefaultNotificationBuilder<'a>,
+}
+
+impl<'a> LocalizedNotificationBuilder<'a> {
+    /// Creates a new builder with the key of the localized alert message and
+    /// the arguments for its format specifiers. Without arguments the
+    /// `loc-args` key is left out.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LocalizedNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let payload = LocalizedNotificationBuilder::new("NEW_MESSAGES", [3])
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"loc-args\":[3],\"loc-key\":\"NEW_MESSAGES\"}}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn new<K, I>(loc_key: K, loc_args: I) -> LocalizedNotificationBuilder<'a>
+    where
+        K: Into<Cow<'a, str>>,
+        I: IntoIterator,
+        I::Item: Into<LocArg<'a>>,
+    {
+        let loc_args = collect_args(loc_args);
+        let mut builder = DefaultNotificationBuilder::new().set_loc_key(loc_key);
+
+        if !loc_args.is_empty() {
+            builder = builder.set_loc_args(loc_args);
+        }
+
+        LocalizedNotificationBuilder { builder }
+    }
+
+    /// The key of the localized title string.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LocalizedNotificationBuilder, NotificationBuilder, LocArg};
+    /// # fn main() {
+    /// let payload = LocalizedNotificationBuilder::new("BODY", Vec::<LocArg>::new())
+    ///     .set_title_loc_key("TITLE")
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"loc-key\":\"BODY\",\"title-loc-key\":\"TITLE\"}}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_title_loc_key<S>(mut self, key: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.builder = self.builder.set_title_loc_key(key);
+        self
+    }
+
+    /// The arguments for the format specifiers of the localized title string.
+    /// Without arguments the `title-loc-args` key is left out.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LocalizedNotificationBuilder, NotificationBuilder, LocArg};
+    /// # fn main() {
+    /// let payload = LocalizedNotificationBuilder::new("BODY", Vec::<LocArg>::new())
+    ///     .set_title_loc_key("TITLE")
+    ///     .set_title_loc_args(vec![String::from("Anna")])
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"loc-key\":\"BODY\",\"title-loc-args\":[\"Anna\"],\"title-loc-key\":\"TITLE\"}}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_title_loc_args<I>(mut self, args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<LocArg<'a>>,
+    {
+        let args = collect_args(args);
+
+        if !args.is_empty() {
+            self.builder = self.builder.set_title_loc_args(args);
+        }
+
+        self
+    }
+
+    /// The key of the localized title for the button that opens the app.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LocalizedNotificationBuilder, NotificationBuilder, LocArg};
+    /// # fn main() {
+    /// let payload = LocalizedNotificationBuilder::new("BODY", Vec::<LocArg>::new())
+    ///     .set_action_loc_key("PLAY")
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"action-loc-key\":\"PLAY\",\"loc-key\":\"BODY\"}}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_action_loc_key<S>(mut self, key: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.builder = self.builder.set_action_loc_key(key);
+        self
+    }
+
+    /// The image shown while the app launches from the notification.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LocalizedNotificationBuilder, NotificationBuilder, LocArg};
+    /// # fn main() {
+    /// let payload = LocalizedNotificationBuilder::new("BODY", Vec::<LocArg>::new())
+    ///     .set_launch_image("launch.png")
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"launch-image\":\"launch.png\",\"loc-key\":\"BODY\"}}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_launch_image<S>(mut self, image: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.builder = self.builder.set_launch_image(image);
+        self
+    }
+
+    /// A number to show on a badge on top of the app icon.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LocalizedNotificationBuilder, NotificationBuilder, LocArg};
+    /// # fn main() {
+    /// let payload = LocalizedNotificationBuilder::new("BODY", Vec::<LocArg>::new())
+    ///     .set_badge(4)
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"loc-key\":\"BODY\"},\"badge\":4}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_badge(mut self, badge: u32) -> Self {
+        self.builder = self.builder.set_badge(badge);
+        self
+    }
+
+    /// The sound to play when receiving the notification.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{LocalizedNotificationBuilder, NotificationBuilder, LocArg};
+    /// # fn main() {
+    /// let payload = LocalizedNotificationBuilder::new("BODY", Vec::<LocArg>::new())
+    ///     .set_sound("ping.flac")
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"loc-key\":\"BODY\"},\"sound\":\"ping.flac\"}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn set_sound<S>(mut self, sound: S) -> Self
+    where
+        S: Into<APSSound<'a>>,
+    {
+        self.builder = self.builder.set_sound(sound);
+        self
+    }
+}
+
+impl<'a> NotificationBuilder<'a> for LocalizedNotificationBuilder<'a> {
+    fn build(self, device_token: &'a str, options: NotificationOptions<'a>) -> Payload<'a> {
+        self.builder.build(device_token, options)
+    }
+}
+
+fn collect_args<'a, I>(args: I) -> Vec<LocArg<'a>>
+where
+    I: IntoIterator,
+    I::Item: Into<LocArg<'a>>,
+{
+    args.into_iter().map(Into::into).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localized_notification_with_every_key() {
+        let payload = LocalizedNotificationBuilder::new(
+            "GAME_PLAY_REQUEST_FORMAT",
+            vec![String::from("Jenna"), String::from("Frank")],
+        )
+        .set_title_loc_key("GAME_PLAY_REQUEST_TITLE")
+        .set_title_loc_args(["Jenna"])
+        .set_action_loc_key("PLAY")
+        .set_launch_image("game.png")
+        .set_badge(2)
+        .set_sound("ping.flac")
+        .build("device-token", Default::default());
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "action-loc-key": "PLAY",
+                    "launch-image": "game.png",
+                    "loc-args": ["Jenna", "Frank"],
+                    "loc-key": "GAME_PLAY_REQUEST_FORMAT",
+                    "title-loc-args": ["Jenna"],
+                    "title-loc-key": "GAME_PLAY_REQUEST_TITLE",
+                },
+                "badge": 2,
+                "sound": "ping.flac",
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_minimal_localized_notification() {
+        let payload = LocalizedNotificationBuilder::new("NEW_MESSAGE", Vec::<&str>::new())
+            .build("device-token", Default::default());
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "loc-key": "NEW_MESSAGE",
+                }
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_localized_notification_with_owned_keys() {
+        let prefix = "GAME";
+
+        let payload = LocalizedNotificationBuilder::new(format!("{}_INVITE", prefix), ["Jenna"])
+            .set_title_loc_key("GAME_TITLE")
+            .set_action_loc_key(format!("{}_PLAY", prefix))
+            .build("device-token", Default::default());
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "action-loc-key": "GAME_PLAY",
+                    "loc-args": ["Jenna"],
+                    "loc-key": "GAME_INVITE",
+                    "title-loc-key": "GAME_TITLE",
+                }
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_localized_notification_without_title_loc_args() {
+        let payload = LocalizedNotificationBuilder::new("NEW_MESSAGE", Vec::<&str>::new())
+            .set_title_loc_key("NEW_MESSAGE_TITLE")
+            .set_title_loc_args(Vec::<&str>::new())
+            .build("device-token", Default::default());
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "loc-key": "NEW_MESSAGE",
+                    "title-loc-key": "NEW_MESSAGE_TITLE",
+                }
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+}
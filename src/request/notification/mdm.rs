@@ -0,0 +1,137 @@
+use crate::request::notification::{NotificationBuilder, NotificationOptions};
+use crate::request::payload::{Payload, APS};
+use serde_json::Value;
+use std::{borrow::Cow, collections::BTreeMap};
+
+/// A builder to create a mobile device management notification payload. The
+/// payload has no `aps` dictionary and only tells the device to contact its
+/// MDM server. Send it to the MDM topic from the push certificate of the
+/// server.
+///
+/// # Example
+///
+/// ```rust
+/// # use a2::request::notification::{MdmNotificationBuilder, NotificationBuilder};
+/// # fn main() {
+/// let payload = MdmNotificationBuilder::new("push-magic")
+///     .build("device_id", Default::default())
+///     .to_json_string()
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MdmNotificationBuilder<'a> {
+    push_magic: Cow<'a, str>,
+}
+
+impl<'a> MdmNotificationBuilder<'a> {
+    /// Creates a new builder with the `PushMagic` string the device sent
+    /// when checking in with the MDM server.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{MdmNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let payload = MdmNotificationBuilder::new("push-magic")
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"mdm\":\"push-magic\"}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn new<S>(push_magic: S) -> MdmNotificationBuilder<'a>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        MdmNotificationBuilder {
+            push_magic: push_magic.into(),
+        }
+    }
+}
+
+impl<'a> NotificationBuilder<'a> for MdmNotificationBuilder<'a> {
+    fn build(self, device_token: &'a str, options: NotificationOptions<'a>) -> Payload<'a> {
+        let mut data = BTreeMap::new();
+        data.insert(Cow::Borrowed("mdm"), Value::from(self.push_magic));
+
+        Payload {
+            aps: APS::default(),
+            device_token,
+            options,
+            data,
+            mdm: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::notification::DefaultNotificationBuilder;
+
+    #[test]
+    fn test_mdm_notification() {
+        let payload = MdmNotificationBuilder::new("9E3E4B2C-6A3B-4F0E-8C4D-2B3C5D6E7F80")
+            .build("device-token", Default::default());
+
+        let expected_payload = json!({
+            "mdm": "9E3E4B2C-6A3B-4F0E-8C4D-2B3C5D6E7F80"
+        })
+        .to_string();
+
+        assert_eq!(expected_payload.len(), payload.size().unwrap());
+        assert_eq!(expected_payload.as_bytes(), &payload.to_vec().unwrap()[..]);
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_mdm_notification_with_owned_push_magic() {
+        let push_magic = String::from("push-magic");
+        let payload = MdmNotificationBuilder::new(push_magic).build("device-token", Default::default());
+
+        assert_eq!("{\"mdm\":\"push-magic\"}", payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_mdm_notification_with_aps_data() {
+        let mut payload = MdmNotificationBuilder::new("push-magic").build("device-token", Default::default());
+        payload.aps.badge = Some(1);
+
+        let expected_payload = json!({
+            "aps": {
+                "badge": 1
+            },
+            "mdm": "push-magic"
+        })
+        .to_string();
+
+        assert!(payload.validate().is_err());
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_custom_data_named_mdm_is_not_an_mdm_notification() {
+        let mut payload = DefaultNotificationBuilder::new().build("device-token", Default::default());
+        payload.add_custom_data("mdm", &"not-push-magic").unwrap();
+
+        let expected_payload = json!({
+            "aps": {},
+            "mdm": "not-push-magic"
+        })
+        .to_string();
+
+        assert!(!payload.mdm);
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_mdm_notification_round_trip() {
+        let json = "{\"mdm\":\"push-magic\"}";
+        let payload = Payload::from_json_str(json, "device-token", Default::default()).unwrap();
+
+        assert!(payload.mdm);
+        assert!(payload.validate().is_ok());
+        assert_eq!(json, payload.to_json_string().unwrap());
+    }
+}
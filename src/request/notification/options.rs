@@ -1,7 +1,8 @@
 use crate::error::Error;
+use crate::request::payload::{MAX_PAYLOAD_SIZE, MAX_VOIP_PAYLOAD_SIZE};
 use std::fmt;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CollapseId<'a> {
     pub value: &'a str,
 }
@@ -20,7 +21,7 @@ impl<'a> CollapseId<'a> {
 }
 
 /// Headers to specify options to the notification.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct NotificationOptions<'a> {
     /// A canonical UUID that identifies the notification. If there is an error
     /// sending the notification, APNs uses this value to identify the
@@ -60,10 +61,166 @@ pub struct NotificationOptions<'a> {
     /// user as a single notification. The value of this key must not exceed 64
     /// bytes.
     pub apns_collapse_id: Option<CollapseId<'a>>,
+
+    /// The type of the notification, sent as the `apns-push-type` header. If
+    /// `None`, the header is omitted and the payload is limited to
+    /// [MAX_PAYLOAD_SIZE](../payload/constant.MAX_PAYLOAD_SIZE.html) bytes.
+    pub apns_push_type: Option<PushType>,
+}
+
+/// A builder to create [NotificationOptions](struct.NotificationOptions.html)
+/// one header at a time. Headers not set are left out of the request.
+///
+/// # Example
+///
+/// ```rust
+/// # use a2::request::notification::{NotificationOptionsBuilder, Priority, PushType};
+/// # fn main() {
+/// let options = NotificationOptionsBuilder::new()
+///     .set_topic("com.example.app")
+///     .set_priority(Priority::High)
+///     .set_push_type(PushType::Alert)
+///     .set_collapse_id("score-update")
+///     .unwrap()
+///     .build();
+///
+/// assert_eq!(Some("com.example.app"), options.apns_topic);
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct NotificationOptionsBuilder<'a> {
+    options: NotificationOptions<'a>,
+}
+
+impl<'a> NotificationOptionsBuilder<'a> {
+    /// Creates a new builder with no headers set.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{NotificationOptions, NotificationOptionsBuilder};
+    /// # fn main() {
+    /// assert_eq!(NotificationOptions::default(), NotificationOptionsBuilder::new().build());
+    /// # }
+    /// ```
+    pub fn new() -> NotificationOptionsBuilder<'a> {
+        NotificationOptionsBuilder::default()
+    }
+
+    /// A canonical UUID identifying the notification, sent as `apns-id`.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::NotificationOptionsBuilder;
+    /// # fn main() {
+    /// let options = NotificationOptionsBuilder::new()
+    ///     .set_id("123e4567-e89b-12d3-a456-4266554400a0")
+    ///     .build();
+    ///
+    /// assert_eq!(Some("123e4567-e89b-12d3-a456-4266554400a0"), options.apns_id);
+    /// # }
+    /// ```
+    pub fn set_id(mut self, apns_id: &'a str) -> Self {
+        self.options.apns_id = Some(apns_id);
+        self
+    }
+
+    /// The UNIX time in seconds after which APNs stops trying to deliver the
+    /// notification, sent as `apns-expiration`. Zero means delivering it only
+    /// once, immediately.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::NotificationOptionsBuilder;
+    /// # fn main() {
+    /// let options = NotificationOptionsBuilder::new()
+    ///     .set_expiration(1685952000)
+    ///     .build();
+    ///
+    /// assert_eq!(Some(1685952000), options.apns_expiration);
+    /// # }
+    /// ```
+    pub fn set_expiration(mut self, apns_expiration: u64) -> Self {
+        self.options.apns_expiration = Some(apns_expiration);
+        self
+    }
+
+    /// The priority of the notification, sent as `apns-priority`.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{NotificationOptionsBuilder, Priority};
+    /// # fn main() {
+    /// let options = NotificationOptionsBuilder::new()
+    ///     .set_priority(Priority::Normal)
+    ///     .build();
+    ///
+    /// assert_eq!(Some(Priority::Normal), options.apns_priority);
+    /// # }
+    /// ```
+    pub fn set_priority(mut self, apns_priority: Priority) -> Self {
+        self.options.apns_priority = Some(apns_priority);
+        self
+    }
+
+    /// The topic of the notification, typically the bundle ID of the app,
+    /// sent as `apns-topic`.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::NotificationOptionsBuilder;
+    /// # fn main() {
+    /// let options = NotificationOptionsBuilder::new()
+    ///     .set_topic("com.example.app")
+    ///     .build();
+    ///
+    /// assert_eq!(Some("com.example.app"), options.apns_topic);
+    /// # }
+    /// ```
+    pub fn set_topic(mut self, apns_topic: &'a str) -> Self {
+        self.options.apns_topic = Some(apns_topic);
+        self
+    }
+
+    /// An identifier for merging notifications into one, sent as
+    /// `apns-collapse-id`. Returns an error if it is longer than 64 bytes.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::NotificationOptionsBuilder;
+    /// # fn main() {
+    /// let options = NotificationOptionsBuilder::new()
+    ///     .set_collapse_id("score-update")
+    ///     .unwrap()
+    ///     .build();
+    ///
+    /// assert_eq!("score-update", options.apns_collapse_id.unwrap().value);
+    /// # }
+    /// ```
+    pub fn set_collapse_id(mut self, apns_collapse_id: &'a str) -> Result<Self, Error> {
+        self.options.apns_collapse_id = Some(CollapseId::new(apns_collapse_id)?);
+        Ok(self)
+    }
+
+    /// The type of the notification, sent as `apns-push-type`.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{NotificationOptionsBuilder, PushType};
+    /// # fn main() {
+    /// let options = NotificationOptionsBuilder::new()
+    ///     .set_push_type(PushType::Background)
+    ///     .build();
+    ///
+    /// assert_eq!(Some(PushType::Background), options.apns_push_type);
+    /// # }
+    /// ```
+    pub fn set_push_type(mut self, apns_push_type: PushType) -> Self {
+        self.options.apns_push_type = Some(apns_push_type);
+        self
+    }
+
+    /// Creates the options with the headers set so far.
+    pub fn build(self) -> NotificationOptions<'a> {
+        self.options
+    }
 }
 
 /// The importance how fast to bring the notification for the user..
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Priority {
     /// Send the push message immediately. Notifications with this priority must
     /// trigger an alert, sound, or badge on the target device. Cannot be used
@@ -75,6 +232,10 @@ pub enum Priority {
     /// grouped and delivered in bursts. They are throttled, and in some cases
     /// are not delivered.
     Normal,
+
+    /// Send the push message only when the device's power considerations
+    /// allow it, without waking the device.
+    Low,
 }
 
 impl fmt::Display for Priority {
@@ -82,12 +243,78 @@ impl fmt::Display for Priority {
         let priority = match self {
             Priority::High => "10",
             Priority::Normal => "5",
+            Priority::Low => "1",
         };
 
         write!(f, "{}", priority)
     }
 }
 
+/// The type of the notification, telling APNs how to deliver it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushType {
+    /// A notification that displays an alert, plays a sound or badges the app icon.
+    Alert,
+
+    /// A notification that delivers content in the background.
+    Background,
+
+    /// A notification that provides information about an incoming VoIP call.
+    Voip,
+
+    /// A notification that contains update information for a watchOS complication.
+    Complication,
+
+    /// A notification that signals changes to a File Provider extension.
+    FileProvider,
+
+    /// A notification that tells managed devices to contact the MDM server.
+    Mdm,
+
+    /// A notification that updates a Live Activity.
+    LiveActivity,
+
+    /// A notification that requests the device's location.
+    Location,
+}
+
+impl PushType {
+    /// The value of the `apns-push-type` header for this type of notification.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::PushType;
+    /// # fn main() {
+    /// assert_eq!("liveactivity", PushType::LiveActivity.header_value());
+    /// # }
+    /// ```
+    pub fn header_value(self) -> &'static str {
+        match self {
+            PushType::Alert => "alert",
+            PushType::Background => "background",
+            PushType::Voip => "voip",
+            PushType::Complication => "complication",
+            PushType::FileProvider => "fileprovider",
+            PushType::Mdm => "mdm",
+            PushType::LiveActivity => "liveactivity",
+            PushType::Location => "location",
+        }
+    }
+
+    /// The largest payload in bytes APNs accepts for this type of notification.
+    pub fn max_payload_size(self) -> usize {
+        match self {
+            PushType::Voip => MAX_VOIP_PAYLOAD_SIZE,
+            _ => MAX_PAYLOAD_SIZE,
+        }
+    }
+}
+
+impl fmt::Display for PushType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.header_value())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,4 +334,61 @@ mod tests {
         let collapse_id = CollapseId::new(str::from_utf8(&long_string).unwrap());
         assert!(collapse_id.is_err());
     }
+
+    #[test]
+    fn test_push_type_max_payload_size() {
+        assert_eq!(MAX_PAYLOAD_SIZE, PushType::Alert.max_payload_size());
+        assert_eq!(MAX_PAYLOAD_SIZE, PushType::Background.max_payload_size());
+        assert_eq!(MAX_VOIP_PAYLOAD_SIZE, PushType::Voip.max_payload_size());
+    }
+
+    #[test]
+    fn test_push_type_header_values() {
+        let expected = [
+            (PushType::Alert, "alert"),
+            (PushType::Background, "background"),
+            (PushType::Voip, "voip"),
+            (PushType::Complication, "complication"),
+            (PushType::FileProvider, "fileprovider"),
+            (PushType::Mdm, "mdm"),
+            (PushType::LiveActivity, "liveactivity"),
+            (PushType::Location, "location"),
+        ];
+
+        for (push_type, value) in expected {
+            assert_eq!(value, push_type.header_value());
+            assert_eq!(value, push_type.to_string());
+        }
+    }
+
+    #[test]
+    fn test_notification_options_builder() {
+        let options = NotificationOptionsBuilder::new()
+            .set_id("123e4567-e89b-12d3-a456-4266554400a0")
+            .set_expiration(1685952000)
+            .set_priority(Priority::Low)
+            .set_topic("com.example.app")
+            .set_collapse_id("score-update")
+            .unwrap()
+            .set_push_type(PushType::Background)
+            .build();
+
+        let expected = NotificationOptions {
+            apns_id: Some("123e4567-e89b-12d3-a456-4266554400a0"),
+            apns_expiration: Some(1685952000),
+            apns_priority: Some(Priority::Low),
+            apns_topic: Some("com.example.app"),
+            apns_collapse_id: Some(CollapseId::new("score-update").unwrap()),
+            apns_push_type: Some(PushType::Background),
+        };
+
+        assert_eq!(expected, options);
+    }
+
+    #[test]
+    fn test_notification_options_builder_with_a_long_collapse_id() {
+        let collapse_id = "a".repeat(65);
+
+        assert!(NotificationOptionsBuilder::new().set_collapse_id(&collapse_id).is_err());
+    }
 }
@@ -0,0 +1,184 @@
+use crate::error::Error;
+use crate::request::notification::{NotificationBuilder, NotificationOptions, Priority, PushType};
+use crate::request::payload::{check_custom_data_key, Payload, APS};
+use erased_serde::Serialize;
+use serde_json::Value;
+use std::{borrow::Cow, collections::BTreeMap};
+
+/// A builder to create a silent notification, waking the app in the
+/// background to process the custom data it carries. There is no way to set
+/// an alert, a sound or a badge, which would make the notification visible.
+/// APNs requires background notifications to use the `background` push type
+/// and priority 5, so the payload is always built with these options.
+///
+/// # Example
+///
+/// ```rust
+/// # use a2::request::notification::{NotificationBuilder, SilentNotificationBuilder};
+/// # fn main() {
+/// let payload = SilentNotificationBuilder::new()
+///     .add_custom_data("sync", &"inbox")
+///     .unwrap()
+///     .build("device_id", Default::default())
+///     .to_json_string()
+///     .unwrap();
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// # use a2::request::notification::SilentNotificationBuilder;
+/// let builder = SilentNotificationBuilder::new().set_body("visible");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SilentNotificationBuilder<'a> {
+    data: BTreeMap<Cow<'a, str>, Value>,
+}
+
+impl<'a> SilentNotificationBuilder<'a> {
+    /// Creates a new builder for a payload with only `content-available` set.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{NotificationBuilder, SilentNotificationBuilder};
+    /// # fn main() {
+    /// let payload = SilentNotificationBuilder::new()
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"content-available\":1}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn new() -> SilentNotificationBuilder<'a> {
+        SilentNotificationBuilder { data: BTreeMap::new() }
+    }
+
+    /// Adds custom data under `root_key` in the root of the payload, the same
+    /// as [Payload::add_custom_data](../payload/struct.Payload.html#method.add_custom_data).
+    /// The `aps` and empty keys return an error, and so does data that would
+    /// make the payload larger than the size limit of the `background` push
+    /// type.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{NotificationBuilder, SilentNotificationBuilder};
+    /// # fn main() {
+    /// let payload = SilentNotificationBuilder::new()
+    ///     .add_custom_data("message_id", &42)
+    ///     .unwrap()
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"content-available\":1},\"message_id\":42}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn add_custom_data<K>(mut self, root_key: K, data: &dyn Serialize) -> Result<Self, Error>
+    where
+        K: Into<Cow<'a, str>>,
+    {
+        let root_key = root_key.into();
+        check_custom_data_key(&root_key)?;
+
+        self.data.insert(root_key, serde_json::to_value(data)?);
+
+        let payload = self.build("", Default::default());
+        payload.validate_size(PushType::Background)?;
+
+        Ok(SilentNotificationBuilder { data: payload.data })
+    }
+}
+
+impl<'a> NotificationBuilder<'a> for SilentNotificationBuilder<'a> {
+    fn build(self, device_token: &'a str, options: NotificationOptions<'a>) -> Payload<'a> {
+        Payload {
+            aps: APS {
+                content_available: Some(1),
+                ..Default::default()
+            },
+            device_token,
+            options: NotificationOptions {
+                apns_priority: Some(Priority::Normal),
+                apns_push_type: Some(PushType::Background),
+                ..options
+            },
+            data: self.data,
+            mdm: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::payload::{PayloadError, MAX_PAYLOAD_SIZE};
+
+    #[test]
+    fn test_silent_notification_with_custom_data() {
+        let payload = SilentNotificationBuilder::new()
+            .add_custom_data("sync", &json!({"mailbox": "inbox", "since": 1685952000}))
+            .unwrap()
+            .add_custom_data(String::from("attempt"), &2)
+            .unwrap()
+            .build("device-token", Default::default());
+
+        let expected_payload = json!({
+            "aps": {
+                "content-available": 1
+            },
+            "attempt": 2,
+            "sync": {
+                "mailbox": "inbox",
+                "since": 1685952000
+            }
+        })
+        .to_string();
+
+        assert!(payload.is_background_only());
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_silent_notification_rejects_reserved_keys() {
+        match SilentNotificationBuilder::new().add_custom_data("aps", &1) {
+            Err(Error::PayloadError(PayloadError::ReservedKey(key))) => assert_eq!("aps", key),
+            other => panic!("expected a reserved key error, got {:?}", other),
+        }
+
+        assert!(SilentNotificationBuilder::new().add_custom_data("", &1).is_err());
+    }
+
+    #[test]
+    fn test_silent_notification_forces_background_options() {
+        let payload = SilentNotificationBuilder::new()
+            .try_build(
+                "device-token",
+                NotificationOptions {
+                    apns_priority: Some(Priority::High),
+                    apns_push_type: Some(PushType::Alert),
+                    apns_topic: Some("com.example.app"),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(Some(Priority::Normal), payload.options.apns_priority);
+        assert_eq!(Some(PushType::Background), payload.options.apns_push_type);
+        assert_eq!(Some("com.example.app"), payload.options.apns_topic);
+    }
+
+    #[test]
+    fn test_silent_notification_rejects_too_large_custom_data() {
+        let builder = SilentNotificationBuilder::new()
+            .add_custom_data("sync", &"a".repeat(MAX_PAYLOAD_SIZE - 100))
+            .unwrap();
+
+        match builder.add_custom_data("more", &"a".repeat(100)) {
+            Err(Error::PayloadError(PayloadError::PayloadTooLarge { size, limit })) => {
+                assert!(size > limit);
+                assert_eq!(MAX_PAYLOAD_SIZE, limit);
+            }
+            other => panic!("expected a payload too large error, got {:?}", other.map(|_| ())),
+        }
+    }
+}
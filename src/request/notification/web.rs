@@ -1,13 +1,42 @@
 use crate::request::notification::{NotificationBuilder, NotificationOptions};
-use crate::request::payload::{APSAlert, Payload, APS};
-use std::collections::BTreeMap;
+use crate::request::payload::{APSAlert, APSSound, Payload, APS};
+use std::{borrow::Cow, collections::BTreeMap};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "kebab-case")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
 pub struct WebPushAlert<'a> {
-    pub title: &'a str,
-    pub body: &'a str,
-    pub action: &'a str,
+    #[serde(borrow)]
+    pub title: Cow<'a, str>,
+    #[serde(borrow)]
+    pub body: Cow<'a, str>,
+    #[serde(borrow)]
+    pub action: Cow<'a, str>,
+}
+
+impl<'a> WebPushAlert<'a> {
+    /// Creates a Safari alert with the title, the body and the label of the
+    /// action button, each either borrowed or an owned `String`.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::WebPushAlert;
+    /// # fn main() {
+    /// let alert = WebPushAlert::new("Hello", format!("World {}", 1), "View");
+    ///
+    /// assert_eq!("World 1", alert.body);
+    /// # }
+    /// ```
+    pub fn new<T, B, A>(title: T, body: B, action: A) -> WebPushAlert<'a>
+    where
+        T: Into<Cow<'a, str>>,
+        B: Into<Cow<'a, str>>,
+        A: Into<Cow<'a, str>>,
+    {
+        WebPushAlert {
+            title: title.into(),
+            body: body.into(),
+            action: action.into(),
+        }
+    }
 }
 
 /// A builder to create a simple APNs notification payload.
@@ -17,7 +46,7 @@ pub struct WebPushAlert<'a> {
 /// ```rust
 /// # use a2::request::notification::{NotificationBuilder, WebNotificationBuilder, WebPushAlert};
 /// # fn main() {
-/// let mut builder = WebNotificationBuilder::new(WebPushAlert {title: "Hello", body: "World", action: "View"}, &["arg1"]);
+/// let mut builder = WebNotificationBuilder::new(WebPushAlert::new("Hello", "World", "View"), &["arg1"]);
 /// builder.set_sound("prööt");
 /// let payload = builder.build("device_id", Default::default())
 ///    .to_json_string().unwrap();
@@ -25,17 +54,19 @@ pub struct WebPushAlert<'a> {
 /// ```
 pub struct WebNotificationBuilder<'a> {
     alert: WebPushAlert<'a>,
-    sound: Option<&'a str>,
-    url_args: &'a [&'a str],
+    sound: Option<Cow<'a, str>>,
+    url_args: Vec<Cow<'a, str>>,
 }
 
 impl<'a> WebNotificationBuilder<'a> {
-    /// Creates a new builder with the minimum amount of content.
+    /// Creates a new builder with the minimum amount of content. The URL
+    /// arguments can be string slices or `String`s and are borrowed from
+    /// `url_args`.
     ///
     /// ```rust
     /// # use a2::request::notification::{WebNotificationBuilder, NotificationBuilder, WebPushAlert};
     /// # fn main() {
-    /// let mut builder = WebNotificationBuilder::new(WebPushAlert {title: "Hello", body: "World", action: "View"}, &["arg1"]);
+    /// let mut builder = WebNotificationBuilder::new(WebPushAlert::new("Hello", "World", "View"), &["arg1"]);
     /// let payload = builder.build("token", Default::default());
     ///
     /// assert_eq!(
@@ -44,11 +75,14 @@ impl<'a> WebNotificationBuilder<'a> {
     /// );
     /// # }
     /// ```
-    pub fn new(alert: WebPushAlert<'a>, url_args: &'a [&'a str]) -> WebNotificationBuilder<'a> {
+    pub fn new<S>(alert: WebPushAlert<'a>, url_args: &'a [S]) -> WebNotificationBuilder<'a>
+    where
+        S: AsRef<str>,
+    {
         WebNotificationBuilder {
             alert,
             sound: None,
-            url_args,
+            url_args: url_args.iter().map(|arg| Cow::Borrowed(arg.as_ref())).collect(),
         }
     }
 
@@ -57,7 +91,7 @@ impl<'a> WebNotificationBuilder<'a> {
     /// ```rust
     /// # use a2::request::notification::{WebNotificationBuilder, NotificationBuilder, WebPushAlert};
     /// # fn main() {
-    /// let mut builder = WebNotificationBuilder::new(WebPushAlert {title: "Hello", body: "World", action: "View"}, &["arg1"]);
+    /// let mut builder = WebNotificationBuilder::new(WebPushAlert::new("Hello", "World", "View"), &["arg1"]);
     /// builder.set_sound("meow");
     /// let payload = builder.build("token", Default::default());
     ///
@@ -67,8 +101,11 @@ impl<'a> WebNotificationBuilder<'a> {
     /// );
     /// # }
     /// ```
-    pub fn set_sound(&mut self, sound: &'a str) -> &mut Self {
-        self.sound = Some(sound);
+    pub fn set_sound<S>(&mut self, sound: S) -> &mut Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.sound = Some(sound.into());
         self
     }
 }
@@ -78,16 +115,14 @@ impl<'a> NotificationBuilder<'a> for WebNotificationBuilder<'a> {
         Payload {
             aps: APS {
                 alert: Some(APSAlert::WebPush(self.alert)),
-                badge: None,
-                sound: self.sound,
-                content_available: None,
-                category: None,
-                mutable_content: None,
+                sound: self.sound.map(APSSound::from),
                 url_args: Some(self.url_args),
+                ..Default::default()
             },
             device_token,
             options,
             data: BTreeMap::new(),
+            mdm: false,
         }
     }
 }
@@ -98,13 +133,35 @@ mod tests {
 
     #[test]
     fn test_webpush_notification() {
+        let payload = WebNotificationBuilder::new(WebPushAlert::new("Hello", "world", "View"), &["arg1"])
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "world",
+                    "action": "View",
+                    "title": "Hello"
+                },
+                "url-args": ["arg1"]
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_webpush_notification_with_multiple_url_args() {
         let payload = WebNotificationBuilder::new(
-            WebPushAlert {
-                action: "View",
-                title: "Hello",
-                body: "world",
-            },
-            &["arg1"],
+            WebPushAlert::new(
+                "Flight A998 Now Boarding",
+                "Boarding has begun for Flight A998.",
+                "View",
+            ),
+            &["boarding", "A998"],
         )
         .build("device-token", Default::default())
         .to_json_string()
@@ -113,11 +170,60 @@ mod tests {
         let expected_payload = json!({
             "aps": {
                 "alert": {
+                    "title": "Flight A998 Now Boarding",
+                    "body": "Boarding has begun for Flight A998.",
+                    "action": "View"
+                },
+                "url-args": ["boarding", "A998"]
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_webpush_notification_without_url_args() {
+        let payload = WebNotificationBuilder::new(WebPushAlert::new("Hello", "world", "View"), &Vec::<&str>::new())
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "title": "Hello",
                     "body": "world",
-                    "action": "View",
-                    "title": "Hello"
+                    "action": "View"
                 },
-                "url-args": ["arg1"]
+                "url-args": []
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload);
+    }
+
+    #[test]
+    fn test_webpush_notification_with_owned_strings() {
+        let url_args = vec![String::from("boarding"), format!("A{}", 998)];
+        let mut builder = WebNotificationBuilder::new(WebPushAlert::new("Hello", "world", "View"), &url_args);
+        builder.set_sound(format!("{}.aiff", "meow"));
+
+        let payload = builder
+            .build("device-token", Default::default())
+            .to_json_string()
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "title": "Hello",
+                    "body": "world",
+                    "action": "View"
+                },
+                "sound": "meow.aiff",
+                "url-args": ["boarding", "A998"]
             }
         })
         .to_string();
@@ -1,12 +1,27 @@
 ///! Payload with `aps` and custom data
 use crate::error::Error;
-use crate::request::notification::{DefaultAlert, NotificationOptions, WebPushAlert};
+use crate::request::notification::{
+    DefaultAlert, LiveActivityEvent, NotificationOptions, Priority, PushType, WebPushAlert,
+};
 use erased_serde::Serialize;
+use serde::ser::SerializeMap;
 use serde_json::{self, Value};
+use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::mem;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// The maximum size of a notification payload in bytes.
+pub const MAX_PAYLOAD_SIZE: usize = 4096;
+
+/// The maximum size of a VoIP notification payload in bytes.
+pub const MAX_VOIP_PAYLOAD_SIZE: usize = 5120;
 
 /// The data and options for a push notification.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Payload<'a> {
     /// Send options
     pub options: NotificationOptions<'a>,
@@ -15,10 +30,79 @@ pub struct Payload<'a> {
     /// The pre-defined notification payload
     pub aps: APS<'a>,
     /// Application specific payload
-    pub data: BTreeMap<&'a str, Value>,
+    pub data: BTreeMap<Cow<'a, str>, Value>,
+    /// A mobile device management notification, sent without the `aps`
+    /// dictionary
+    pub mdm: bool,
 }
 
 impl<'a> Payload<'a> {
+    /// Parses a payload from its JSON, the inverse of
+    /// [to_json_string](#method.to_json_string). Keys other than `aps` are
+    /// kept as custom data. Returns an error naming the problem and its
+    /// position if the JSON is not a valid payload.
+    ///
+    /// Strings are borrowed from `json` or copied from it, and may contain
+    /// escape sequences everywhere.
+    ///
+    /// ```rust
+    /// # use a2::request::payload::Payload;
+    /// # fn main() {
+    /// let json = r#"{"aps":{"alert":"Hello","badge":1},"order_id":42}"#;
+    /// let payload = Payload::from_json_str(json, "token", Default::default()).unwrap();
+    ///
+    /// assert_eq!(Some(1), payload.aps.badge);
+    /// assert_eq!(json, &payload.to_json_string().unwrap());
+    /// # }
+    /// ```
+    pub fn from_json_str(
+        json: &'a str,
+        device_token: &'a str,
+        options: NotificationOptions<'a>,
+    ) -> Result<Payload<'a>, Error> {
+        #[derive(Deserialize)]
+        struct RawPayload<'a> {
+            #[serde(borrow)]
+            aps: Option<APS<'a>>,
+            #[serde(flatten, borrow)]
+            data: BTreeMap<Cow<'a, str>, Value>,
+        }
+
+        let raw: RawPayload<'a> = serde_json::from_str(json).map_err(PayloadError::InvalidJson)?;
+
+        Ok(Payload {
+            options,
+            device_token,
+            mdm: raw.aps.is_none() && raw.data.contains_key("mdm"),
+            aps: raw.aps.unwrap_or_default(),
+            data: raw.data,
+        })
+    }
+
+    /// A payload only setting the badge on the app icon, with no alert or
+    /// sound. A `badge` of zero removes the badge.
+    ///
+    /// ```rust
+    /// # use a2::request::payload::Payload;
+    /// # fn main() {
+    /// let payload = Payload::new_badge(3, "token", Default::default());
+    ///
+    /// assert_eq!("{\"aps\":{\"badge\":3}}", &payload.to_json_string().unwrap());
+    /// # }
+    /// ```
+    pub fn new_badge(badge: u32, device_token: &'a str, options: NotificationOptions<'a>) -> Payload<'a> {
+        Payload {
+            options,
+            device_token,
+            aps: APS {
+                badge: Some(badge),
+                ..Default::default()
+            },
+            data: BTreeMap::new(),
+            mdm: false,
+        }
+    }
+
     /// Client-specific custom data to be added in the payload.
     /// The `root_key` defines the JSON key in the root of the request
     /// data, and `data` the object containing custom data. The `data`
@@ -26,6 +110,12 @@ impl<'a> Payload<'a> {
     /// collection or if needing more strict type definitions, any struct
     /// that has `#[derive(Serialize)]` from [Serde](https://serde.rs).
     ///
+    /// The `aps` key is reserved for the notification content and using it as
+    /// the `root_key` returns an error. Data that would make the payload
+    /// larger than the size limit of its push type, see
+    /// [validate_size](#method.validate_size), is not added and returns an
+    /// error as well.
+    ///
     /// Using a `HashMap`:
     ///
     /// ```rust
@@ -41,7 +131,7 @@ impl<'a> Payload<'a> {
     /// payload.add_custom_data("foo_data", &custom_data).unwrap();
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"content-available\":1,\"mutable-content\":0},\"foo_data\":{\"foo\":\"bar\"}}",
+    ///     "{\"aps\":{\"content-available\":1},\"foo_data\":{\"foo\":\"bar\"}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// # }
@@ -66,70 +156,1928 @@ impl<'a> Payload<'a> {
     /// payload.add_custom_data("foo_data", &custom_data).unwrap();
     ///
     /// assert_eq!(
-    ///     "{\"aps\":{\"content-available\":1,\"mutable-content\":0},\"foo_data\":{\"foo\":\"bar\"}}",
+    ///     "{\"aps\":{\"content-available\":1},\"foo_data\":{\"foo\":\"bar\"}}",
     ///     &payload.to_json_string().unwrap()
     /// );
     /// }
     /// ```
-    pub fn add_custom_data(&mut self, root_key: &'a str, data: &dyn Serialize) -> Result<&mut Self, Error> {
-        self.data.insert(root_key, serde_json::to_value(data)?);
+    pub fn add_custom_data<K>(&mut self, root_key: K, data: &dyn Serialize) -> Result<&mut Self, Error>
+    where
+        K: Into<Cow<'a, str>>,
+    {
+        let root_key = root_key.into();
+        check_custom_data_key(&root_key)?;
+
+        let previous = self.data.insert(root_key.clone(), serde_json::to_value(data)?);
+
+        if let Err(e) = self.validate_size(self.push_type()) {
+            match previous {
+                Some(value) => self.data.insert(root_key, value),
+                None => self.data.remove(&root_key),
+            };
+
+            return Err(e);
+        }
+
+        Ok(self)
+    }
+
+    /// Adds several custom data entries at once. Either all entries are
+    /// added, or none if any key is empty or reserved, a value fails to
+    /// serialize or the payload would grow larger than the size limit of its
+    /// push type.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # use std::collections::BTreeMap;
+    /// # fn main() {
+    /// let mut data = BTreeMap::new();
+    /// data.insert("order_id", 42);
+    /// data.insert("customer_id", 7);
+    ///
+    /// let mut payload = DefaultNotificationBuilder::new()
+    ///     .set_content_available()
+    ///     .build("token", Default::default());
+    ///
+    /// payload.extend_custom_data(data).unwrap();
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"content-available\":1},\"customer_id\":7,\"order_id\":42}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn extend_custom_data<I, K, V>(&mut self, data: I) -> Result<&mut Self, Error>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Cow<'a, str>>,
+        V: serde::Serialize,
+    {
+        let entries = data
+            .into_iter()
+            .map(|(key, value)| {
+                let key = key.into();
+                check_custom_data_key(&key)?;
+                Ok((key, serde_json::to_value(value)?))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut data = self.data.clone();
+        data.extend(entries);
+
+        let previous = mem::replace(&mut self.data, data);
+
+        if let Err(e) = self.validate_size(self.push_type()) {
+            self.data = previous;
+            return Err(e);
+        }
+
+        Ok(self)
+    }
+
+    /// Adds a key to the `aps` dictionary that has no dedicated field in
+    /// [APS](struct.APS.html), such as one introduced in a new iOS version.
+    /// Returns an error if the key is already managed by this crate.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut payload = DefaultNotificationBuilder::new()
+    ///     .set_body("a body")
+    ///     .build("token", Default::default());
+    ///
+    /// payload.add_aps_data("new-key", &1).unwrap();
+    ///
+    /// assert!(payload.add_aps_data("badge", &1).is_err());
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"body\":\"a body\"},\"new-key\":1}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn add_aps_data<K>(&mut self, key: K, data: &dyn Serialize) -> Result<&mut Self, Error>
+    where
+        K: Into<Cow<'a, str>>,
+    {
+        let key = key.into();
+
+        if APS_KEYS.contains(&&*key) {
+            return Err(PayloadError::ReservedKey(key.into_owned()).into());
+        }
+
+        self.aps.extra.insert(key, serde_json::to_value(data)?);
 
         Ok(self)
     }
 
+    /// Checks the payload for key combinations APNs does not deliver as
+    /// intended. Returns an error if `content-available` is combined with an
+    /// alert, a sound or a badge while sending as a background push with
+    /// [Priority::Normal or Low](../notification/enum.Priority.html) or
+    /// [PushType::Background](../notification/enum.PushType.html), if
+    /// `mutable-content` is set without an alert, if a Live Activity is
+    /// started without an alert, if the alert has no text to show, or if an
+    /// MDM notification has any `aps` content.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder, NotificationOptions, Priority};
+    /// # fn main() {
+    /// let options = NotificationOptions {
+    ///     apns_priority: Some(Priority::Normal),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let payload = DefaultNotificationBuilder::new()
+    ///     .set_body("a body")
+    ///     .set_sound("ping")
+    ///     .set_content_available()
+    ///     .build("token", options);
+    ///
+    /// assert!(payload.validate().is_err());
+    /// # }
+    /// ```
+    pub fn validate(&self) -> Result<(), Error> {
+        let aps = &self.aps;
+
+        if self.mdm && *aps != APS::default() {
+            return Err(PayloadError::ConflictingKeys("an MDM notification must not have aps content").into());
+        }
+
+        let background_push = matches!(self.options.apns_priority, Some(Priority::Normal | Priority::Low))
+            || self.options.apns_push_type == Some(PushType::Background);
+
+        if aps.content_available.is_some() && background_push && !self.is_background_only() {
+            return Err(PayloadError::ConflictingKeys(
+                "a background push with content-available must not have an alert, a sound or a badge",
+            )
+            .into());
+        }
+
+        if aps.alert.as_ref().is_some_and(APSAlert::is_empty) {
+            return Err(PayloadError::EmptyAlert.into());
+        }
+
+        if aps.mutable_content.is_some() && aps.alert.is_none() {
+            return Err(PayloadError::ConflictingKeys("mutable-content has no effect without an alert").into());
+        }
+
+        if aps.event == Some(LiveActivityEvent::Start) && aps.alert.is_none() {
+            return Err(PayloadError::ConflictingKeys("starting a Live Activity requires an alert").into());
+        }
+
+        Ok(())
+    }
+
+    /// A copy of the payload with the badge set to `badge`, for sending the
+    /// same notification to many devices with their own unread counts.
+    /// Borrowed strings stay borrowed in the copy.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let payload = DefaultNotificationBuilder::new()
+    ///     .set_body("a body")
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"body\":\"a body\"},\"badge\":3}}",
+    ///     &payload.with_badge(3).to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn with_badge(&self, badge: u32) -> Payload<'a> {
+        let mut payload = self.clone();
+        payload.aps.badge = Some(badge);
+
+        payload
+    }
+
+    /// True if the payload only wakes the app in the background: it has
+    /// `content-available` set and no alert, sound or badge.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let payload = DefaultNotificationBuilder::new()
+    ///     .set_content_available()
+    ///     .build("token", Default::default());
+    ///
+    /// assert!(payload.is_background_only());
+    ///
+    /// let payload = DefaultNotificationBuilder::new()
+    ///     .set_content_available()
+    ///     .set_badge(1)
+    ///     .build("token", Default::default());
+    ///
+    /// assert!(!payload.is_background_only());
+    /// # }
+    /// ```
+    pub fn is_background_only(&self) -> bool {
+        let aps = &self.aps;
+
+        aps.content_available.is_some() && aps.alert.is_none() && aps.sound.is_none() && aps.badge.is_none()
+    }
+
+    /// Checks the serialized payload fits in the size limit of the given push
+    /// type, [MAX_VOIP_PAYLOAD_SIZE](constant.MAX_VOIP_PAYLOAD_SIZE.html) for
+    /// VoIP and [MAX_PAYLOAD_SIZE](constant.MAX_PAYLOAD_SIZE.html) otherwise,
+    /// returning an error with the actual size if it does not.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder, PushType};
+    /// # fn main() {
+    /// let payload = DefaultNotificationBuilder::new()
+    ///     .set_body("a body")
+    ///     .build("token", Default::default());
+    ///
+    /// assert!(payload.validate_size(PushType::Alert).is_ok());
+    /// # }
+    /// ```
+    pub fn validate_size(&self, push_type: PushType) -> Result<(), Error> {
+        self.validate_size_with_limit(push_type.max_payload_size())
+    }
+
+    /// True if the serialized payload fits in the size limit APNs has for
+    /// the given push type.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder, PushType};
+    /// # fn main() {
+    /// let payload = DefaultNotificationBuilder::new()
+    ///     .set_body("a body")
+    ///     .build("token", Default::default());
+    ///
+    /// assert!(payload.fits(PushType::Alert));
+    /// # }
+    /// ```
+    pub fn fits(&self, push_type: PushType) -> bool {
+        self.validate_size(push_type).is_ok()
+    }
+
+    /// Checks the serialized payload fits in `limit` bytes, such as
+    /// [MAX_VOIP_PAYLOAD_SIZE](constant.MAX_VOIP_PAYLOAD_SIZE.html) for VoIP
+    /// notifications.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # use a2::request::payload::MAX_VOIP_PAYLOAD_SIZE;
+    /// # fn main() {
+    /// let payload = DefaultNotificationBuilder::new()
+    ///     .set_content_available()
+    ///     .build("token", Default::default());
+    ///
+    /// assert!(payload.validate_size_with_limit(MAX_VOIP_PAYLOAD_SIZE).is_ok());
+    /// # }
+    /// ```
+    pub fn validate_size_with_limit(&self, limit: usize) -> Result<(), Error> {
+        let size = self.size()?;
+
+        if size > limit {
+            return Err(PayloadError::PayloadTooLarge { size, limit }.into());
+        }
+
+        Ok(())
+    }
+
+    /// Shortens the literal alert body until the serialized payload fits in
+    /// `limit` bytes, always cutting on a character boundary and keeping
+    /// common emoji sequences, flags and combining marks with their base
+    /// character. This is not full grapheme cluster segmentation, so rarer
+    /// sequences may still be split. Returns the number of bytes
+    /// removed from the body, or an error if the payload does not fit even
+    /// with an empty body, leaving the payload unchanged. Safari alerts are
+    /// not truncated.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut payload = DefaultNotificationBuilder::new()
+    ///     .set_body("Hello, world!")
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(8, payload.truncate_body_to_fit(34).unwrap());
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"body\":\"Hello\"}}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn truncate_body_to_fit(&mut self, limit: usize) -> Result<usize, Error> {
+        let original = self.body_mut().cloned();
+        let original_len = original.as_ref().map_or(0, |body| body.len());
+        let mut size = self.size()?;
+
+        while size > limit {
+            let body = match self.body_mut() {
+                Some(body) if !body.is_empty() => body,
+                _ => {
+                    if let Some(original) = original {
+                        self.replace_body(original);
+                    }
+
+                    return Err(PayloadError::PayloadTooLarge { size, limit }.into());
+                }
+            };
+
+            let end = truncation_point(body, body.len().saturating_sub(size - limit));
+
+            match body {
+                Cow::Borrowed(text) => *text = &text[..end],
+                Cow::Owned(text) => text.truncate(end),
+            }
+
+            size = self.size()?;
+        }
+
+        Ok(original_len - self.body_mut().map_or(0, |body| body.len()))
+    }
+
+    /// Like [truncate_body_to_fit](#method.truncate_body_to_fit), but ends the
+    /// shortened body with an ellipsis. Returns the number of bytes the body
+    /// shrank, which is zero when escaped characters made room for a longer
+    /// ellipsis, or an error if the payload does not fit even with an empty
+    /// body, leaving the payload unchanged. Only the body text is modified.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let mut payload = DefaultNotificationBuilder::new()
+    ///     .set_body("Hello, world!")
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(5, payload.truncate_to_fit(37).unwrap());
+    /// assert_eq!(
+    ///     "{\"aps\":{\"alert\":{\"body\":\"Hello…\"}}}",
+    ///     &payload.to_json_string().unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn truncate_to_fit(&mut self, limit: usize) -> Result<usize, Error> {
+        let mut size = self.size()?;
+
+        if size <= limit {
+            return Ok(0);
+        }
+
+        let body = match self.body_mut() {
+            Some(body) => mem::take(body),
+            None => return Err(PayloadError::PayloadTooLarge { size, limit }.into()),
+        };
+
+        let empty_size = self.size()?;
+
+        if empty_size > limit {
+            self.replace_body(body);
+
+            return Err(PayloadError::PayloadTooLarge {
+                size: empty_size,
+                limit,
+            }
+            .into());
+        }
+
+        let original_len = body.len();
+        let mut end = original_len;
+        let mut truncated_len = original_len;
+
+        while size > limit && end > 0 {
+            end = truncation_point(&body, end.saturating_sub(size - limit));
+
+            let truncated = match end {
+                0 => String::new(),
+                _ => format!("{}{}", &body[..end], ELLIPSIS),
+            };
+
+            truncated_len = truncated.len();
+            self.replace_body(Cow::Owned(truncated));
+
+            size = self.size()?;
+        }
+
+        Ok(original_len.saturating_sub(truncated_len))
+    }
+
     /// Combine the APS payload and the custom data to a final payload JSON.
     /// Returns an error if serialization fails.
     #[allow(clippy::wrong_self_convention)]
     pub fn to_json_string(mut self) -> Result<String, Error> {
-        let aps_data = serde_json::to_value(&self.aps)?;
+        if self.has_aps() {
+            let aps_data = serde_json::to_value(&self.aps)?;
 
-        self.data.insert("aps", aps_data);
+            self.data.insert(Cow::Borrowed("aps"), aps_data);
+        }
 
         Ok(serde_json::to_string(&self.data)?)
     }
-}
 
-/// The pre-defined notification data.
-#[derive(Serialize, Default, Debug, Clone)]
-#[serde(rename_all = "kebab-case")]
-#[allow(clippy::upper_case_acronyms)]
-pub struct APS<'a> {
-    /// The notification content. Can be empty for silent notifications.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub alert: Option<APSAlert<'a>>,
+    /// The size of the payload JSON in bytes, counted without keeping the
+    /// serialized output in memory.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let payload = DefaultNotificationBuilder::new()
+    ///     .set_body("a body")
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(35, payload.size().unwrap());
+    /// # }
+    /// ```
+    pub fn size(&self) -> Result<usize, Error> {
+        let mut counter = ByteCounter(0);
+        self.to_writer(&mut counter)?;
 
-    /// A number shown on top of the app icon.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub badge: Option<u32>,
+        Ok(counter.0)
+    }
 
-    /// The name of the sound file to play when user receives the notification.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sound: Option<&'a str>,
+    /// Writes the payload JSON into `writer` without copying the custom data.
+    /// The output is the same as from
+    /// [to_json_string](#method.to_json_string).
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let payload = DefaultNotificationBuilder::new()
+    ///     .set_body("a body")
+    ///     .build("token", Default::default());
+    ///
+    /// let mut buffer = Vec::new();
+    /// payload.to_writer(&mut buffer).unwrap();
+    ///
+    /// assert_eq!(b"{\"aps\":{\"alert\":{\"body\":\"a body\"}}}", &buffer[..]);
+    /// # }
+    /// ```
+    pub fn to_writer<W: io::Write>(&self, writer: W) -> Result<(), Error> {
+        Ok(serde_json::to_writer(writer, &self.body()?)?)
+    }
 
-    /// Set to one for silent notifications.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub content_available: Option<u8>,
+    /// The payload JSON as bytes, ready to be used as a request body. The
+    /// JSON is written straight into the returned buffer, without an
+    /// intermediate `String`.
+    ///
+    /// ```rust
+    /// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+    /// # fn main() {
+    /// let payload = DefaultNotificationBuilder::new()
+    ///     .set_body("a body")
+    ///     .build("token", Default::default());
+    ///
+    /// assert_eq!(b"{\"aps\":{\"alert\":{\"body\":\"a body\"}}}".to_vec(), payload.to_vec().unwrap());
+    /// # }
+    /// ```
+    pub fn to_vec(&self) -> Result<Vec<u8>, Error> {
+        let mut buffer = Vec::new();
+        self.to_writer(&mut buffer)?;
 
-    /// When a notification includes the category key, the system displays the
-    /// actions for that category as buttons in the banner or alert interface.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub category: Option<&'a str>,
+        Ok(buffer)
+    }
 
-    /// If set to one, the app can change the notification content before
-    /// displaying it to the user.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub mutable_content: Option<u8>,
+    /// The push type the payload is sent with, `alert` if not set.
+    fn push_type(&self) -> PushType {
+        self.options.apns_push_type.unwrap_or(PushType::Alert)
+    }
 
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub url_args: Option<&'a [&'a str]>,
+    /// MDM notifications carry only the `mdm` key, without an `aps`
+    /// dictionary, unless aps content was added to one by mistake.
+    fn has_aps(&self) -> bool {
+        !(self.mdm && self.aps == APS::default())
+    }
+
+    fn body(&self) -> Result<PayloadBody<'_, 'a>, Error> {
+        let aps = match self.has_aps() {
+            true => Some(serde_json::to_value(&self.aps)?),
+            false => None,
+        };
+
+        Ok(PayloadBody { aps, data: &self.data })
+    }
+
+    fn replace_body(&mut self, body: Cow<'a, str>) {
+        if let Some(current) = self.body_mut() {
+            *current = body;
+        }
+    }
+
+    fn body_mut(&mut self) -> Option<&mut Cow<'a, str>> {
+        match self.aps.alert.as_mut()? {
+            APSAlert::Body(body) => Some(body),
+            APSAlert::Default(alert) => alert.body_mut(),
+            APSAlert::WebPush(_) => None,
+        }
+    }
 }
 
-/// Different notification content types.
-#[derive(Serialize, Debug, Clone)]
-#[serde(untagged)]
-pub enum APSAlert<'a> {
-    /// A notification that supports all of the iOS features
-    Default(DefaultAlert<'a>),
-    /// Safari web push notification
-    WebPush(WebPushAlert<'a>),
+/// Parses a payload from its JSON with
+/// [from_json_str](struct.Payload.html#method.from_json_str), leaving the
+/// device token empty and the options at their defaults. Malformed input
+/// returns an `InvalidJson` error.
+///
+/// **The device token must be set before sending**, the client rejects a
+/// payload without one.
+///
+/// ```rust
+/// # use a2::request::payload::Payload;
+/// # fn main() {
+/// let mut payload = Payload::try_from(r#"{"aps":{"badge":2},"order_id":42}"#).unwrap();
+/// payload.device_token = "token";
+///
+/// assert_eq!(Some(2), payload.aps.badge);
+/// # }
+/// ```
+impl<'a> TryFrom<&'a str> for Payload<'a> {
+    type Error = Error;
+
+    fn try_from(json: &'a str) -> Result<Self, Self::Error> {
+        Payload::from_json_str(json, "", Default::default())
+    }
+}
+
+/// Writes the payload JSON, the same as
+/// [to_json_string](struct.Payload.html#method.to_json_string) returns.
+///
+/// ```rust
+/// # use a2::request::notification::{DefaultNotificationBuilder, NotificationBuilder};
+/// # fn main() {
+/// let payload = DefaultNotificationBuilder::new()
+///     .set_body("a body")
+///     .build("token", Default::default());
+///
+/// assert_eq!("{\"aps\":{\"alert\":{\"body\":\"a body\"}}}", payload.to_string());
+/// # }
+/// ```
+impl fmt::Display for Payload<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let body = self.body().map_err(|_| fmt::Error)?;
+        let json = serde_json::to_string(&body).map_err(|_| fmt::Error)?;
+
+        f.write_str(&json)
+    }
+}
+
+pub(crate) fn check_custom_data_key(key: &str) -> Result<(), Error> {
+    match key {
+        "" => Err(PayloadError::EmptyKey.into()),
+        "aps" => Err(PayloadError::ReservedKey(key.to_string()).into()),
+        _ => Ok(()),
+    }
+}
+
+/// The root object of the payload JSON, with `aps` in the same sorted position
+/// as in the output of `to_json_string`.
+struct PayloadBody<'b, 'a> {
+    aps: Option<Value>,
+    data: &'b BTreeMap<Cow<'a, str>, Value>,
+}
+
+impl serde::Serialize for PayloadBody<'_, '_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        let mut aps = self.aps.as_ref();
+
+        for (key, value) in self.data.iter().filter(|(key, _)| **key != "aps") {
+            if &**key > "aps" {
+                if let Some(aps) = aps.take() {
+                    map.serialize_entry("aps", aps)?;
+                }
+            }
+
+            map.serialize_entry(key, value)?;
+        }
+
+        if let Some(aps) = aps {
+            map.serialize_entry("aps", aps)?;
+        }
+
+        map.end()
+    }
+}
+
+/// Appended to an alert body shortened by `truncate_to_fit`.
+const ELLIPSIS: &str = "…";
+
+/// A writer discarding its input, counting the bytes written.
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The largest index up to `max` where `text` can be cut on a character
+/// boundary without separating a character from the marks listed in
+/// `is_grapheme_extend`, breaking a zero width joiner sequence or splitting a
+/// flag. This covers common emoji and combining sequences, but is not a full
+/// implementation of Unicode grapheme clusters.
+fn truncation_point(text: &str, max: usize) -> usize {
+    let mut end = max.min(text.len());
+
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    while end > 0 && splits_sequence(text, end) {
+        end = text[..end].char_indices().next_back().map_or(0, |(i, _)| i);
+    }
+
+    end
+}
+
+/// Whether cutting `text` at the character boundary `at` would separate one
+/// of the sequences `truncation_point` keeps whole.
+fn splits_sequence(text: &str, at: usize) -> bool {
+    let (before, after) = text.split_at(at);
+    let next = after.chars().next();
+
+    if next.is_some_and(is_grapheme_extend) || before.ends_with('\u{200d}') {
+        return true;
+    }
+
+    // Flags are pairs of regional indicators, so a cut between two of them
+    // is only safe after an even number.
+    next.is_some_and(is_regional_indicator)
+        && before.chars().rev().take_while(|&c| is_regional_indicator(c)).count() % 2 == 1
+}
+
+/// Combining marks, the zero width joiner, combining marks for symbols such
+/// as the keycap, variation selectors, skin tone modifiers and emoji tag
+/// characters, which attach to the preceding character.
+fn is_grapheme_extend(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{036f}'
+            | '\u{200d}'
+            | '\u{20d0}'..='\u{20ff}'
+            | '\u{fe00}'..='\u{fe0f}'
+            | '\u{1f3fb}'..='\u{1f3ff}'
+            | '\u{e0020}'..='\u{e007f}'
+    )
+}
+
+/// The regional indicator symbols, pairs of which make up a flag.
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1f1e6}'..='\u{1f1ff}').contains(&c)
+}
+
+/// The pre-defined notification data.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+#[allow(clippy::upper_case_acronyms)]
+pub struct APS<'a> {
+    /// The notification content. Can be empty for silent notifications.
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    pub alert: Option<APSAlert<'a>>,
+
+    /// A number shown on top of the app icon.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub badge: Option<u32>,
+
+    /// The sound to play when user receives the notification.
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    pub sound: Option<APSSound<'a>>,
+
+    /// Set to one for silent notifications.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_available: Option<u8>,
+
+    /// When a notification includes the category key, the system displays the
+    /// actions for that category as buttons in the banner or alert interface.
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    pub category: Option<Cow<'a, str>>,
+
+    /// If set to one, the app can change the notification content before
+    /// displaying it to the user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mutable_content: Option<u8>,
+
+    /// An app-specific identifier for grouping related notifications.
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    pub thread_id: Option<Cow<'a, str>>,
+
+    /// The identifier of the window brought forward when the notification is
+    /// opened. A free-form value defined by the app.
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    pub target_content_id: Option<Cow<'a, str>>,
+
+    /// The importance and delivery timing of the notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interruption_level: Option<InterruptionLevel>,
+
+    /// A score between 0.0 and 1.0 used to pick the featured notification in a
+    /// notification summary.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relevance_score: Option<f64>,
+
+    /// The criteria the system evaluates to decide whether to show the
+    /// notification in the current Focus.
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    pub filter_criteria: Option<Cow<'a, str>>,
+
+    /// The action of a Live Activity notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<LiveActivityEvent>,
+
+    /// The UNIX time in seconds when the Live Activity content was produced.
+    /// Updates older than the currently shown content are discarded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+
+    /// The dynamic content of a Live Activity, matching its `ContentState`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_state: Option<Value>,
+
+    /// The UNIX time in seconds after which the Live Activity content is
+    /// considered outdated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale_date: Option<u64>,
+
+    /// The UNIX time in seconds when an ended Live Activity is removed from the
+    /// Lock Screen.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dismissal_date: Option<u64>,
+
+    /// The static attributes of a Live Activity started with a notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<Value>,
+
+    /// The name of the activity attributes struct of a Live Activity started
+    /// with a notification.
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    pub attributes_type: Option<Cow<'a, str>>,
+
+    /// Set to one to have a Live Activity started with a broadcast
+    /// notification report its own push token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_push_token: Option<u8>,
+
+    /// The values substituted into the `urlFormatString` of a Safari push
+    /// package. Serialized even when empty.
+    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    pub url_args: Option<Vec<Cow<'a, str>>>,
+
+    /// Keys not supported by this crate yet, serialized into the `aps`
+    /// dictionary next to the other fields. See
+    /// [Payload::add_aps_data](struct.Payload.html#method.add_aps_data).
+    #[serde(flatten, borrow)]
+    pub extra: BTreeMap<Cow<'a, str>, Value>,
+}
+
+/// The `aps` keys managed by the fields of [APS](struct.APS.html).
+const APS_KEYS: &[&str] = &[
+    "alert",
+    "badge",
+    "sound",
+    "content-available",
+    "category",
+    "mutable-content",
+    "thread-id",
+    "target-content-id",
+    "interruption-level",
+    "relevance-score",
+    "filter-criteria",
+    "event",
+    "timestamp",
+    "content-state",
+    "stale-date",
+    "dismissal-date",
+    "attributes",
+    "attributes-type",
+    "input-push-token",
+    "url-args",
+];
+
+/// Different notification content types.
+///
+/// When deserializing, a plain string becomes `Body`, a dictionary with
+/// exactly the Safari keys becomes `WebPush` and any other dictionary becomes
+/// `Default`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum APSAlert<'a> {
+    /// A plain string alert, shown as the body of the notification
+    Body(Cow<'a, str>),
+    /// Safari web push notification
+    #[serde(borrow)]
+    WebPush(WebPushAlert<'a>),
+    /// A notification that supports all of the iOS features
+    #[serde(borrow)]
+    Default(DefaultAlert<'a>),
+}
+
+impl APSAlert<'_> {
+    fn is_empty(&self) -> bool {
+        match self {
+            APSAlert::Body(body) => body.is_empty(),
+            APSAlert::WebPush(alert) => alert.title.is_empty() && alert.body.is_empty(),
+            APSAlert::Default(alert) => alert.is_empty(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for APSAlert<'a> {
+    fn from(body: &'a str) -> Self {
+        APSAlert::Body(Cow::Borrowed(body))
+    }
+}
+
+impl From<String> for APSAlert<'_> {
+    fn from(body: String) -> Self {
+        APSAlert::Body(Cow::Owned(body))
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for APSAlert<'a> {
+    fn from(body: Cow<'a, str>) -> Self {
+        APSAlert::Body(body)
+    }
+}
+
+impl<'a> From<DefaultAlert<'a>> for APSAlert<'a> {
+    fn from(alert: DefaultAlert<'a>) -> Self {
+        APSAlert::Default(alert)
+    }
+}
+
+impl<'a> From<WebPushAlert<'a>> for APSAlert<'a> {
+    fn from(alert: WebPushAlert<'a>) -> Self {
+        APSAlert::WebPush(alert)
+    }
+}
+
+/// The sound played when the notification arrives.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum APSSound<'a> {
+    /// The name of a sound file in the main bundle of the app or in the
+    /// `Library/Sounds` folder of the app container.
+    #[serde(borrow)]
+    File(Cow<'a, str>),
+    /// A sound for critical alerts.
+    #[serde(borrow)]
+    Critical(CriticalSound<'a>),
+}
+
+impl<'a> APSSound<'a> {
+    /// A sound for critical alerts, played even if the device is muted or in
+    /// Focus. The `volume` must be between 0.0 (silent) and 1.0 (full volume).
+    /// Requires a special entitlement from Apple.
+    ///
+    /// ```rust
+    /// # use a2::request::payload::APSSound;
+    /// # fn main() {
+    /// let sound = APSSound::critical("alarm.aiff", 0.5).unwrap();
+    ///
+    /// assert_eq!(
+    ///     "{\"critical\":1,\"name\":\"alarm.aiff\",\"volume\":0.5}",
+    ///     &serde_json::to_string(&sound).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn critical<S>(name: S, volume: f64) -> Result<Self, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        if !(0.0..=1.0).contains(&volume) {
+            return Err(PayloadError::InvalidVolume(volume).into());
+        }
+
+        Ok(APSSound::Critical(CriticalSound {
+            critical: 1,
+            name: name.into(),
+            volume,
+        }))
+    }
+
+    /// A sound for critical alerts, with the `volume` clamped between 0.0 and
+    /// 1.0. A NaN volume plays at full volume.
+    ///
+    /// ```rust
+    /// # use a2::request::payload::APSSound;
+    /// # fn main() {
+    /// let sound = APSSound::critical_clamped("alarm.aiff", 1.5);
+    ///
+    /// assert_eq!(
+    ///     "{\"critical\":1,\"name\":\"alarm.aiff\",\"volume\":1.0}",
+    ///     &serde_json::to_string(&sound).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn critical_clamped<S>(name: S, volume: f64) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let volume = if volume.is_nan() { 1.0 } else { volume.clamp(0.0, 1.0) };
+
+        APSSound::Critical(CriticalSound {
+            critical: 1,
+            name: name.into(),
+            volume,
+        })
+    }
+}
+
+impl APSSound<'static> {
+    /// The system sound for notifications.
+    pub const DEFAULT: APSSound<'static> = APSSound::File(Cow::Borrowed("default"));
+}
+
+impl<'a> From<&'a str> for APSSound<'a> {
+    fn from(name: &'a str) -> Self {
+        APSSound::File(Cow::Borrowed(name))
+    }
+}
+
+impl From<String> for APSSound<'_> {
+    fn from(name: String) -> Self {
+        APSSound::File(Cow::Owned(name))
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for APSSound<'a> {
+    fn from(name: Cow<'a, str>) -> Self {
+        APSSound::File(name)
+    }
+}
+
+/// The sound dictionary of a critical alert. Created with
+/// [APSSound::critical](enum.APSSound.html#method.critical).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CriticalSound<'a> {
+    critical: u8,
+    #[serde(borrow)]
+    name: Cow<'a, str>,
+    volume: f64,
+}
+
+/// The importance and delivery timing of a notification.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum InterruptionLevel {
+    /// Added to the notification list without lighting up the screen or
+    /// playing a sound.
+    Passive,
+    /// Presented immediately, lighting up the screen and possibly playing a
+    /// sound.
+    Active,
+    /// Presented immediately, breaking through system notification controls
+    /// such as Focus.
+    TimeSensitive,
+    /// Presented immediately, ignoring the mute switch and Focus. Requires a
+    /// special entitlement from Apple.
+    Critical,
+}
+
+impl InterruptionLevel {
+    /// The value sent in the `interruption-level` key.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InterruptionLevel::Passive => "passive",
+            InterruptionLevel::Active => "active",
+            InterruptionLevel::TimeSensitive => "time-sensitive",
+            InterruptionLevel::Critical => "critical",
+        }
+    }
+}
+
+impl fmt::Display for InterruptionLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for InterruptionLevel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "passive" => Ok(InterruptionLevel::Passive),
+            "active" => Ok(InterruptionLevel::Active),
+            "time-sensitive" => Ok(InterruptionLevel::TimeSensitive),
+            "critical" => Ok(InterruptionLevel::Critical),
+            _ => Err(Error::InvalidOptions(format!("Unknown interruption level: {}", s))),
+        }
+    }
+}
+
+/// A reason why a notification payload is not valid.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum PayloadError {
+    /// The relevance score was outside of the `0.0..=1.0` range.
+    #[error("Invalid relevance score {0}, expected a value between 0.0 and 1.0")]
+    InvalidRelevanceScore(f64),
+
+    /// A date was set before the UNIX epoch.
+    #[error("The {0} is before the UNIX epoch")]
+    DateBeforeUnixEpoch(&'static str),
+
+    /// The volume of a critical alert sound was outside of the `0.0..=1.0`
+    /// range.
+    #[error("Invalid critical sound volume {0}, expected a value between 0.0 and 1.0")]
+    InvalidVolume(f64),
+
+    /// Custom data was added with a key reserved by APNs.
+    #[error("The key {0:?} is reserved and cannot hold custom data")]
+    ReservedKey(String),
+
+    /// Custom data was added with an empty key.
+    #[error("Custom data keys cannot be empty")]
+    EmptyKey,
+
+    /// The payload combines keys that do not work together.
+    #[error("Conflicting payload keys: {0}")]
+    ConflictingKeys(&'static str),
+
+    /// The payload has an alert without any text to show.
+    #[error("The alert has no text to show")]
+    EmptyAlert,
+
+    /// The serialized payload is larger than APNs accepts.
+    #[error("The payload is {size} bytes, over the limit of {limit} bytes")]
+    PayloadTooLarge { size: usize, limit: usize },
+
+    /// The payload could not be parsed from JSON.
+    #[error("Invalid payload JSON: {0}")]
+    InvalidJson(serde_json::Error),
+
+    /// The number of localization arguments does not match the format
+    /// specifiers of the localized template.
+    #[error("The {key} has {actual} arguments, but the template expects {expected}")]
+    LocArgsMismatch {
+        key: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::notification::{DefaultNotificationBuilder, LocArg, NotificationBuilder};
+
+    #[test]
+    fn test_custom_data_with_nested_values() {
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .build("device-token", Default::default());
+
+        payload
+            .add_custom_data("message_id", &42)
+            .unwrap()
+            .add_custom_data("links", &json!({"open": "app://inbox/42", "tags": ["a", "b"]}))
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "the body"
+                }
+            },
+            "links": {
+                "open": "app://inbox/42",
+                "tags": ["a", "b"]
+            },
+            "message_id": 42
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_custom_data_with_the_same_key_overwrites() {
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .build("device-token", Default::default());
+
+        payload
+            .add_custom_data("order_id", &"first")
+            .unwrap()
+            .add_custom_data("order_id", &"second")
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "the body"
+                }
+            },
+            "order_id": "second"
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_custom_data_with_a_nested_struct() {
+        #[derive(Serialize)]
+        struct Attachment {
+            url: &'static str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            mime_type: Option<&'static str>,
+        }
+
+        #[derive(Serialize)]
+        struct Message {
+            id: u64,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            thread: Option<&'static str>,
+            attachments: Vec<Attachment>,
+        }
+
+        let message = Message {
+            id: 1,
+            thread: None,
+            attachments: vec![
+                Attachment {
+                    url: "https://example.com/a.png",
+                    mime_type: Some("image/png"),
+                },
+                Attachment {
+                    url: "https://example.com/b",
+                    mime_type: None,
+                },
+            ],
+        };
+
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .build("device-token", Default::default());
+
+        payload.add_custom_data("message", &message).unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "the body"
+                }
+            },
+            "message": {
+                "id": 1,
+                "attachments": [
+                    {"url": "https://example.com/a.png", "mime_type": "image/png"},
+                    {"url": "https://example.com/b"}
+                ]
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_custom_data_with_an_empty_key() {
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .build("device-token", Default::default());
+
+        assert!(payload.add_custom_data("", &1).is_err());
+        assert!(payload.data.is_empty());
+    }
+
+    #[test]
+    fn test_extend_custom_data_is_atomic() {
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .build("device-token", Default::default());
+
+        assert!(payload.extend_custom_data(vec![("order_id", 1), ("aps", 2)]).is_err());
+        assert!(payload.extend_custom_data(vec![("order_id", 1), ("", 2)]).is_err());
+        assert!(payload.data.is_empty());
+
+        payload
+            .extend_custom_data(vec![("order_id", json!(1)), ("tags", json!(["a", "b"]))])
+            .unwrap();
+
+        assert_eq!(Some(&json!(1)), payload.data.get("order_id"));
+        assert_eq!(Some(&json!(["a", "b"])), payload.data.get("tags"));
+    }
+
+    #[test]
+    fn test_aps_data() {
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .set_badge(1)
+            .build("device-token", Default::default());
+
+        payload
+            .add_aps_data("future-key", &json!({"enabled": true}))
+            .unwrap()
+            .add_aps_data("another-key", &"value")
+            .unwrap();
+
+        let expected_payload = json!({
+            "aps": {
+                "alert": {
+                    "body": "the body"
+                },
+                "another-key": "value",
+                "badge": 1,
+                "future-key": {
+                    "enabled": true
+                }
+            }
+        })
+        .to_string();
+
+        assert_eq!(expected_payload, payload.clone().to_json_string().unwrap());
+
+        for key in APS_KEYS {
+            assert!(payload.add_aps_data(key, &1).is_err());
+        }
+    }
+
+    #[test]
+    fn test_custom_data_with_aps_key() {
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .build("device-token", Default::default());
+
+        assert!(payload.add_custom_data("aps", &json!({"badge": 1})).is_err());
+        assert!(payload.data.is_empty());
+    }
+
+    #[test]
+    fn test_to_writer_matches_to_json_string() {
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_title("the title")
+            .set_body("the body")
+            .set_badge(2)
+            .build("device-token", Default::default());
+
+        payload
+            .add_custom_data("alpha", &json!({"b": 1, "a": 2}))
+            .unwrap()
+            .add_custom_data("zulu", &"last")
+            .unwrap();
+
+        let mut buffer = Vec::new();
+        payload.to_writer(&mut buffer).unwrap();
+
+        assert_eq!(payload.to_vec().unwrap(), buffer);
+        assert_eq!(payload.to_json_string().unwrap().into_bytes(), buffer);
+    }
+
+    #[test]
+    fn test_size_matches_the_serialized_length() {
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_title("the title")
+            .set_body("Grüße 👋")
+            .build("device-token", Default::default());
+
+        payload.add_custom_data("id", &42).unwrap();
+
+        let size = payload.size().unwrap();
+
+        assert_eq!(payload.to_json_string().unwrap().len(), size);
+    }
+
+    #[test]
+    fn test_validate_size() {
+        let body = "a".repeat(MAX_PAYLOAD_SIZE);
+
+        let payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .build("device-token", Default::default());
+
+        assert!(payload.validate_size(PushType::Alert).is_ok());
+
+        let payload = DefaultNotificationBuilder::new()
+            .set_body(&body)
+            .build("device-token", Default::default());
+
+        match payload.validate_size(PushType::Alert) {
+            Err(Error::PayloadError(PayloadError::PayloadTooLarge { size, limit })) => {
+                assert_eq!(MAX_PAYLOAD_SIZE + 29, size);
+                assert_eq!(MAX_PAYLOAD_SIZE, limit);
+            }
+            other => panic!("expected a payload too large error, got {:?}", other),
+        }
+
+        assert!(DefaultNotificationBuilder::new()
+            .set_body(&body)
+            .try_build("device-token", Default::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_validate_size_at_the_limit() {
+        // `{"aps":{"alert":{"body":""}}}` is 29 bytes without the body.
+        let fits = "a".repeat(MAX_PAYLOAD_SIZE - 29);
+        let too_large = "a".repeat(MAX_PAYLOAD_SIZE - 28);
+
+        let payload = DefaultNotificationBuilder::new()
+            .set_body(&fits)
+            .build("device-token", Default::default());
+
+        assert_eq!(MAX_PAYLOAD_SIZE, payload.size().unwrap());
+        assert!(payload.validate_size(PushType::Alert).is_ok());
+
+        let payload = DefaultNotificationBuilder::new()
+            .set_body(&too_large)
+            .build("device-token", Default::default());
+
+        assert_eq!(MAX_PAYLOAD_SIZE + 1, payload.size().unwrap());
+        assert!(payload.validate_size(PushType::Alert).is_err());
+        assert!(payload.validate_size(PushType::Background).is_err());
+        assert!(payload.validate_size(PushType::Voip).is_ok());
+    }
+
+    #[test]
+    fn test_validate_voip_size_at_the_limit() {
+        let fits = "a".repeat(MAX_VOIP_PAYLOAD_SIZE - 29);
+        let too_large = "a".repeat(MAX_VOIP_PAYLOAD_SIZE - 28);
+
+        let payload = DefaultNotificationBuilder::new()
+            .set_body(&fits)
+            .build("device-token", Default::default());
+
+        assert!(payload.validate_size(PushType::Voip).is_ok());
+
+        let payload = DefaultNotificationBuilder::new()
+            .set_body(&too_large)
+            .build("device-token", Default::default());
+
+        match payload.validate_size(PushType::Voip) {
+            Err(Error::PayloadError(PayloadError::PayloadTooLarge { size, limit })) => {
+                assert_eq!(MAX_VOIP_PAYLOAD_SIZE + 1, size);
+                assert_eq!(MAX_VOIP_PAYLOAD_SIZE, limit);
+            }
+            other => panic!("expected a payload too large error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_truncate_body_to_fit() {
+        let body = format!("{}👨‍👩‍👧 family", "a".repeat(MAX_PAYLOAD_SIZE - 40));
+
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_body(&body)
+            .build("device-token", Default::default());
+
+        assert_eq!(0, payload.truncate_body_to_fit(MAX_PAYLOAD_SIZE + 100).unwrap());
+
+        let removed = payload.truncate_body_to_fit(MAX_PAYLOAD_SIZE).unwrap();
+
+        assert_eq!("👨‍👩‍👧 family".len(), removed);
+        assert!(payload.validate_size(PushType::Alert).is_ok());
+    }
+
+    #[test]
+    fn test_truncate_body_to_fit_without_room_for_the_body() {
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .build("device-token", Default::default());
+
+        payload
+            .data
+            .insert(Cow::Borrowed("blob"), json!("a".repeat(MAX_PAYLOAD_SIZE)));
+
+        assert!(payload.truncate_body_to_fit(MAX_PAYLOAD_SIZE).is_err());
+        assert!(payload.to_json_string().unwrap().contains("the body"));
+    }
+
+    #[test]
+    fn test_truncate_to_fit() {
+        let body = format!("{}👍🏽 thumbs up", "a".repeat(MAX_PAYLOAD_SIZE));
+
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_title("the title")
+            .set_body(&body)
+            .set_loc_args(&["kept"])
+            .build("device-token", Default::default());
+
+        payload.data.insert(Cow::Borrowed("id"), json!(42));
+
+        let removed = payload.truncate_to_fit(MAX_PAYLOAD_SIZE).unwrap();
+        let size = payload.size().unwrap();
+
+        assert!(size <= MAX_PAYLOAD_SIZE && size > MAX_PAYLOAD_SIZE - 4);
+        assert!(removed > "👍🏽 thumbs up".len());
+
+        let json = payload.to_json_string().unwrap();
+
+        assert!(json.contains("a…\""));
+        assert!(json.contains("\"loc-args\":[\"kept\"]"));
+        assert!(json.contains("\"id\":42"));
+    }
+
+    #[test]
+    fn test_truncate_to_fit_with_an_escaped_suffix() {
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_body("abcd\u{1}")
+            .build("device-token", Default::default());
+
+        let limit = payload.size().unwrap() - 1;
+
+        assert_eq!(0, payload.truncate_to_fit(limit).unwrap());
+        assert!(payload.size().unwrap() <= limit);
+        assert!(payload.to_json_string().unwrap().contains("\"abcd…\""));
+    }
+
+    #[test]
+    fn test_truncate_to_fit_without_room_for_the_body() {
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .build("device-token", Default::default());
+
+        payload
+            .data
+            .insert(Cow::Borrowed("blob"), json!("a".repeat(MAX_PAYLOAD_SIZE)));
+
+        assert!(payload.truncate_to_fit(MAX_PAYLOAD_SIZE).is_err());
+        assert!(payload.to_json_string().unwrap().contains("the body"));
+    }
+
+    #[test]
+    fn test_truncation_point() {
+        let text = "ab👨‍👩‍👧c";
+
+        assert_eq!(2, truncation_point(text, 5));
+        assert_eq!(2, truncation_point(text, text.len() - 2));
+        assert_eq!(text.len() - 1, truncation_point(text, text.len() - 1));
+        assert_eq!(3, truncation_point("e\u{301}x", 3));
+        assert_eq!(0, truncation_point("e\u{301}x", 2));
+    }
+
+    #[test]
+    fn test_truncation_point_with_flags() {
+        let text = "a🇫🇮🇸🇪";
+
+        assert_eq!(1, truncation_point(text, 5));
+        assert_eq!(9, truncation_point(text, 9));
+        assert_eq!(9, truncation_point(text, 13));
+        assert_eq!(text.len(), truncation_point(text, text.len()));
+    }
+
+    #[test]
+    fn test_truncation_point_with_keycaps() {
+        let text = "x1\u{fe0f}\u{20e3}";
+
+        assert_eq!(1, truncation_point(text, 2));
+        assert_eq!(1, truncation_point(text, 5));
+        assert_eq!(text.len(), truncation_point(text, text.len()));
+    }
+
+    #[test]
+    fn test_validate_content_available_with_alert_and_sound() {
+        let build = |priority| {
+            DefaultNotificationBuilder::new()
+                .set_body("the body")
+                .set_sound("ping")
+                .set_content_available()
+                .build(
+                    "device-token",
+                    NotificationOptions {
+                        apns_priority: priority,
+                        ..Default::default()
+                    },
+                )
+        };
+
+        assert!(build(Some(Priority::Normal)).validate().is_err());
+        assert!(build(Some(Priority::High)).validate().is_ok());
+        assert!(build(None).validate().is_ok());
+
+        let silent = DefaultNotificationBuilder::new().set_content_available().build(
+            "device-token",
+            NotificationOptions {
+                apns_priority: Some(Priority::Normal),
+                ..Default::default()
+            },
+        );
+
+        assert!(silent.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_background_push_with_user_visible_keys() {
+        let options = NotificationOptions {
+            apns_push_type: Some(PushType::Background),
+            ..Default::default()
+        };
+
+        let conflicting = DefaultNotificationBuilder::new()
+            .set_content_available()
+            .set_badge(3)
+            .build("device-token", options.clone());
+
+        assert!(!conflicting.is_background_only());
+
+        match conflicting.validate() {
+            Err(Error::PayloadError(PayloadError::ConflictingKeys(_))) => (),
+            other => panic!("expected a conflicting keys error, got {:?}", other),
+        }
+
+        assert!(DefaultNotificationBuilder::new()
+            .set_content_available()
+            .set_sound("ping")
+            .try_build("device-token", options.clone())
+            .is_err());
+
+        let clean = DefaultNotificationBuilder::new()
+            .set_content_available()
+            .try_build("device-token", options)
+            .unwrap();
+
+        assert!(clean.is_background_only());
+
+        let alert = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .set_content_available()
+            .build("device-token", Default::default());
+
+        assert!(!alert.is_background_only());
+        assert!(alert.validate().is_ok());
+    }
+
+    #[test]
+    fn test_payload_from_json_str_round_trip() {
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_title("the \"quoted\" title")
+            .set_body("the \"quoted\" body")
+            .set_loc_args([LocArg::from("narf"), LocArg::from(2)])
+            .set_sound("ping")
+            .set_thread_id("thread")
+            .build("device-token", Default::default());
+
+        payload
+            .add_custom_data("order", &json!({"id": 42, "items": ["a", "b"]}))
+            .unwrap()
+            .add_aps_data("future-key", &true)
+            .unwrap();
+
+        let json = payload.clone().to_json_string().unwrap();
+        let parsed = Payload::from_json_str(&json, "device-token", Default::default()).unwrap();
+
+        assert_eq!(payload, parsed);
+        assert_eq!(json, parsed.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_payload_from_json_str_without_aps() {
+        let json = r#"{"mdm":"push-magic"}"#;
+        let payload = Payload::from_json_str(json, "device-token", Default::default()).unwrap();
+
+        assert_eq!(APS::default(), payload.aps);
+        assert_eq!(json, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_payload_from_invalid_json_str() {
+        let error = Payload::from_json_str(r#"{"aps":{"badge":"one"}}"#, "device-token", Default::default())
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("invalid type: string \"one\""), "{}", error);
+        assert!(Payload::from_json_str("[]", "device-token", Default::default()).is_err());
+    }
+
+    #[test]
+    fn test_aps_deserialization_round_trip() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_title("the title")
+            .set_body("the body")
+            .set_loc_args(&["narf", "poit"])
+            .set_badge(3)
+            .set_critical_sound("alarm.aiff", 0.5)
+            .unwrap()
+            .set_interruption_level(InterruptionLevel::Critical)
+            .build("device-token", Default::default());
+
+        let json = serde_json::to_string(&payload.aps).unwrap();
+        let aps: APS = serde_json::from_str(&json).unwrap();
+
+        assert!(matches!(aps.alert, Some(APSAlert::Default(_))));
+        assert!(matches!(aps.sound, Some(APSSound::Critical(_))));
+        assert_eq!(Some(InterruptionLevel::Critical), aps.interruption_level);
+        assert_eq!(json, serde_json::to_string(&aps).unwrap());
+        assert_eq!(payload.aps, aps);
+    }
+
+    #[test]
+    fn test_aps_deserialization_of_alert_forms() {
+        let aps: APS = serde_json::from_str(r#"{"alert":"Hello","sound":"default"}"#).unwrap();
+
+        assert!(matches!(aps.alert, Some(APSAlert::Body(ref body)) if body == "Hello"));
+        assert_eq!(Some(APSSound::DEFAULT), aps.sound);
+
+        let json = r#"{"alert":{"title":"Hello","body":"world","action":"View"},"url-args":["boarding","A998"]}"#;
+        let aps: APS = serde_json::from_str(json).unwrap();
+
+        assert!(matches!(aps.alert, Some(APSAlert::WebPush(_))));
+        assert_eq!(
+            Some(vec![Cow::Borrowed("boarding"), Cow::Borrowed("A998")]),
+            aps.url_args
+        );
+        assert_eq!(json, serde_json::to_string(&aps).unwrap());
+
+        let json = r#"{"alert":{"title":"Hello","body":"world","launch-image":"splash.png"}}"#;
+        let aps: APS = serde_json::from_str(json).unwrap();
+
+        assert!(matches!(aps.alert, Some(APSAlert::Default(_))));
+        assert_eq!(json, serde_json::to_string(&aps).unwrap());
+    }
+
+    #[test]
+    fn test_payload_equality() {
+        let template = DefaultNotificationBuilder::new()
+            .set_title("the title")
+            .set_body("the body")
+            .set_relevance_score(0.5)
+            .unwrap()
+            .build("device-token", Default::default());
+
+        let mut payload = template.clone();
+
+        assert_eq!(template, payload);
+
+        payload.aps.badge = Some(1);
+
+        assert_ne!(template, payload);
+    }
+
+    #[test]
+    fn test_default_payload() {
+        let payload = Payload::default();
+
+        assert_eq!("{}", serde_json::to_string(&payload.aps).unwrap());
+        assert_eq!("{\"aps\":{}}", payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_sound_serialization() {
+        let file = APSSound::from("ping.flac");
+        let critical = APSSound::critical("alarm.aiff", 0.8).unwrap();
+
+        assert_eq!(json!("ping.flac"), serde_json::to_value(&file).unwrap());
+        assert_eq!(
+            json!({"critical": 1, "name": "alarm.aiff", "volume": 0.8}),
+            serde_json::to_value(&critical).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_critical_sound_volume_out_of_range() {
+        assert!(APSSound::critical("alarm.aiff", 1.1).is_err());
+        assert!(APSSound::critical("alarm.aiff", -0.1).is_err());
+        assert!(APSSound::critical("alarm.aiff", f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_critical_sound_volume_error() {
+        assert!(APSSound::critical("alarm.aiff", 0.5).is_ok());
+
+        match APSSound::critical("alarm.aiff", 1.5) {
+            Err(Error::PayloadError(PayloadError::InvalidVolume(volume))) => assert_eq!(1.5, volume),
+            other => panic!("expected an invalid volume error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_interruption_level_serialization() {
+        let levels = vec![
+            InterruptionLevel::Passive,
+            InterruptionLevel::Active,
+            InterruptionLevel::TimeSensitive,
+            InterruptionLevel::Critical,
+        ];
+
+        for level in levels.into_iter() {
+            let serialized = serde_json::to_value(level).unwrap();
+            assert_eq!(json!(level.as_str()), serialized);
+        }
+    }
+
+    #[test]
+    fn test_interruption_level_from_str() {
+        let levels = vec![
+            InterruptionLevel::Passive,
+            InterruptionLevel::Active,
+            InterruptionLevel::TimeSensitive,
+            InterruptionLevel::Critical,
+        ];
+
+        for level in levels.into_iter() {
+            assert_eq!(level, level.to_string().parse::<InterruptionLevel>().unwrap());
+        }
+
+        assert!("loud".parse::<InterruptionLevel>().is_err());
+    }
+
+    #[test]
+    fn test_payload_display() {
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .set_badge(2)
+            .build("device-token", Default::default());
+
+        payload.add_custom_data("zzz", &"last").unwrap();
+        payload.add_custom_data("abc", &"first").unwrap();
+
+        assert_eq!(payload.clone().to_json_string().unwrap(), format!("{}", payload));
+        assert_eq!(
+            "{\"abc\":\"first\",\"aps\":{\"alert\":{\"body\":\"the body\"},\"badge\":2},\"zzz\":\"last\"}",
+            payload.to_string()
+        );
+    }
+
+    #[test]
+    fn test_aps_alert_from_strings() {
+        let expected = APSAlert::Body(Cow::Borrowed("hi"));
+
+        assert_eq!(expected, APSAlert::from("hi"));
+        assert_eq!(expected, APSAlert::from(String::from("hi")));
+        assert_eq!(expected, APSAlert::from(Cow::Borrowed("hi")));
+        assert!(matches!(APSAlert::from("hi"), APSAlert::Body(Cow::Borrowed(_))));
+
+        let mut payload = DefaultNotificationBuilder::new().build("device-token", Default::default());
+        payload.aps.alert = Some("hi".into());
+
+        assert_eq!("{\"aps\":{\"alert\":\"hi\"}}", payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_with_badge_keeps_strings_borrowed() {
+        let payload = DefaultNotificationBuilder::new()
+            .set_title("the title")
+            .set_body("the body")
+            .set_badge(1)
+            .build("device-token", Default::default());
+
+        let mut with_badge = payload.with_badge(7);
+
+        assert_eq!(Some(1), payload.aps.badge);
+        assert_eq!(Some(7), with_badge.aps.badge);
+        assert_eq!(payload.device_token, with_badge.device_token);
+        assert!(matches!(with_badge.body_mut(), Some(Cow::Borrowed("the body"))));
+    }
+
+    #[test]
+    fn test_aps_alert_from_alert_dictionaries() {
+        let alert = DefaultAlert::default();
+        assert_eq!(APSAlert::Default(alert.clone()), APSAlert::from(alert));
+
+        let web = WebPushAlert::new("the title", "the body", "View");
+        assert_eq!(APSAlert::WebPush(web.clone()), APSAlert::from(web));
+    }
+
+    #[test]
+    fn test_badge_only_payload() {
+        let payload = Payload::new_badge(12, "device-token", Default::default());
+
+        assert_eq!("device-token", payload.device_token);
+        assert_eq!("{\"aps\":{\"badge\":12}}", payload.to_json_string().unwrap());
+
+        let cleared = Payload::new_badge(0, "device-token", Default::default());
+
+        assert_eq!("{\"aps\":{\"badge\":0}}", cleared.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_validate_empty_alert() {
+        let empty_body = DefaultNotificationBuilder::new()
+            .set_body("")
+            .try_build("device-token", Default::default());
+
+        assert!(matches!(empty_body, Err(Error::PayloadError(PayloadError::EmptyAlert))));
+
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_badge(1)
+            .build("device-token", Default::default());
+
+        payload.aps.alert = Some(DefaultAlert::default().into());
+        assert!(matches!(
+            payload.validate(),
+            Err(Error::PayloadError(PayloadError::EmptyAlert))
+        ));
+
+        payload.aps.alert = Some("".into());
+        assert!(matches!(
+            payload.validate(),
+            Err(Error::PayloadError(PayloadError::EmptyAlert))
+        ));
+
+        let localized = DefaultNotificationBuilder::new()
+            .set_loc_key("NEW_MESSAGE")
+            .try_build("device-token", Default::default());
+
+        assert!(localized.is_ok());
+    }
+
+    #[test]
+    fn test_payload_try_from_str() {
+        let json = r#"{"aps":{"alert":{"body":"the body","title":"the title"},"sound":"ping"},"trace":{"id":7}}"#;
+        let payload = Payload::try_from(json).unwrap();
+
+        assert_eq!("", payload.device_token);
+        assert_eq!(Some(&json!({"id": 7})), payload.data.get("trace"));
+        assert_eq!(json, payload.to_json_string().unwrap());
+
+        assert!(matches!(
+            Payload::try_from("{\"aps\":"),
+            Err(Error::PayloadError(PayloadError::InvalidJson(_)))
+        ));
+    }
+
+    #[test]
+    fn test_fits_every_push_type_at_its_limit() {
+        let push_types = [
+            PushType::Alert,
+            PushType::Background,
+            PushType::Voip,
+            PushType::Complication,
+            PushType::FileProvider,
+            PushType::Mdm,
+            PushType::LiveActivity,
+            PushType::Location,
+        ];
+
+        for push_type in push_types {
+            let limit = push_type.max_payload_size();
+
+            // `{"aps":{"alert":{"body":""}}}` is 29 bytes without the body.
+            let fits = "a".repeat(limit - 29);
+            let too_large = "a".repeat(limit - 28);
+
+            let payload = DefaultNotificationBuilder::new()
+                .set_body(&fits)
+                .build("device-token", Default::default());
+
+            assert_eq!(limit, payload.size().unwrap());
+            assert!(payload.fits(push_type), "{:?}", push_type);
+
+            let payload = DefaultNotificationBuilder::new()
+                .set_body(&too_large)
+                .build("device-token", Default::default());
+
+            assert!(!payload.fits(push_type), "{:?}", push_type);
+        }
+    }
+
+    #[test]
+    fn test_payload_from_json_with_escaped_strings() {
+        let json = r#"{"aps":{"sound":"ping\\pong.aiff","category":"say \"hi\"","thread-id":"line\nbreak","url-args":["a\"b"],"new\"key":1},"custom \"key\"":1}"#;
+        let payload = Payload::from_json_str(json, "device-token", Default::default()).unwrap();
+
+        assert_eq!(Some(APSSound::from("ping\\pong.aiff")), payload.aps.sound);
+        assert_eq!(Some("say \"hi\""), payload.aps.category.as_deref());
+        assert_eq!(Some("line\nbreak"), payload.aps.thread_id.as_deref());
+        assert_eq!(Some(vec![Cow::Borrowed("a\"b")]), payload.aps.url_args);
+        assert_eq!(Some(&json!(1)), payload.aps.extra.get("new\"key"));
+        assert_eq!(Some(&json!(1)), payload.data.get("custom \"key\""));
+        assert_eq!(json, payload.to_json_string().unwrap());
+    }
+
+    #[test]
+    fn test_custom_data_over_the_size_limit_is_not_added() {
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_body("the body")
+            .build("device-token", Default::default());
+
+        payload.add_custom_data("blob", &"small").unwrap();
+
+        assert!(matches!(
+            payload.add_custom_data("blob", &"a".repeat(MAX_PAYLOAD_SIZE)),
+            Err(Error::PayloadError(PayloadError::PayloadTooLarge { .. }))
+        ));
+        assert!(payload
+            .extend_custom_data(vec![("id", "1".to_string()), ("other", "a".repeat(MAX_PAYLOAD_SIZE))])
+            .is_err());
+        assert!(payload.add_custom_data("other", &"a".repeat(MAX_PAYLOAD_SIZE)).is_err());
+
+        assert_eq!(Some(&json!("small")), payload.data.get("blob"));
+        assert_eq!(1, payload.data.len());
+
+        let mut voip = DefaultNotificationBuilder::new().build(
+            "device-token",
+            NotificationOptions {
+                apns_push_type: Some(PushType::Voip),
+                ..Default::default()
+            },
+        );
+
+        assert!(voip.add_custom_data("blob", &"a".repeat(MAX_PAYLOAD_SIZE)).is_ok());
+    }
+
+    #[test]
+    fn test_payload_from_json_with_an_escaped_safari_alert() {
+        let json = r#"{"aps":{"alert":{"action":"View","body":"b","title":"Say \"hi\""}}}"#;
+        let payload = Payload::from_json_str(json, "device-token", Default::default()).unwrap();
+
+        assert_eq!(
+            Some(APSAlert::WebPush(WebPushAlert::new("Say \"hi\"", "b", "View"))),
+            payload.aps.alert
+        );
+        assert_eq!(json, payload.to_json_string().unwrap());
+    }
 }
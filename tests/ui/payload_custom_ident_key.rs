@@ -0,0 +1,5 @@
+use a2::payload;
+
+fn main() {
+    let _payload = payload! { custom: { msg_id: 42 } };
+}